@@ -0,0 +1,1185 @@
+use crate::config::PromptTemplate;
+use anyhow::{Context, Result};
+use reqwest::blocking::Client;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Once, mpsc};
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_OLLAMA_ENDPOINT: &str = "http://localhost:11434";
+pub const DEFAULT_OPENAI_ENDPOINT: &str = "http://localhost:8080/v1";
+pub const DEFAULT_OLLAMA_MODEL: &str = "llama3.2:1b";
+pub const DEFAULT_PROMPT_NAME: &str = "default";
+const DEFAULT_LANGUAGE: &str = "English";
+const DEFAULT_LENGTH: &str = "2-3 sentences";
+const DEFAULT_DIFF_PROMPT: &str = "You are an expert software engineer. Create a concise branch description of {length} in {language} from this git diff. Focus on the main changes and their purpose. Do not include implementation details or file names.";
+const DEFAULT_TEXT_PROMPT: &str = "You are an expert software engineer. Create a concise branch description of {length} in {language} from this content. Focus on the main goals and requirements. Keep it professional and actionable.";
+pub const DEFAULT_MR_PROMPT: &str = "You are an expert software engineer. Write a merge request description in Markdown in {language} for branch {branch} from the branch description, commit list and diff below. Use the sections '## Summary' (2-3 sentences on purpose and approach), '## Changes' (short bullet list) and '## Testing' (how to verify). Output only the description.";
+pub const DEFAULT_COMMIT_PROMPT: &str = "You are an expert software engineer. Write a conventional commit message in {language} for the changes below, using the branch description and commit list as context. Format: 'type(scope): subject' (feat, fix, refactor, docs, test, chore; subject at most 72 characters, imperative mood), a blank line, then a short body explaining what and why. Output only the commit message.";
+/// Content tokens per request, about the 8000 characters small local models handle well
+pub const DEFAULT_CHUNK_TOKENS: usize = 2000;
+
+const CHUNK_DIFF_PROMPT: &str = "You are an expert software engineer. Summarize the changes in this part of a larger git diff in at most 3 short bullet points. Name the affected area or module, skip line-level details.";
+const CHUNK_TEXT_PROMPT: &str = "You are an expert software engineer. Summarize this part of a larger document in at most 3 short bullet points. Keep goals, requirements and issue titles, skip boilerplate.";
+
+/// A chat model that turns a system and a user prompt into a completion.
+pub trait AiProvider {
+    /// Human readable name used in progress messages, e.g. "Ollama (llama3.2:1b)"
+    fn name(&self) -> String;
+
+    /// Generates a completion, passing every piece of text to `on_token` as it arrives.
+    /// Returns an error when the generation is cancelled with Ctrl-C.
+    fn chat_stream(
+        &self,
+        system_prompt: &str,
+        user_prompt: &str,
+        timeout: Duration,
+        on_token: &mut dyn FnMut(&str),
+    ) -> Result<String>;
+
+    fn chat(&self, system_prompt: &str, user_prompt: &str, timeout: Duration) -> Result<String> {
+        self.chat_stream(system_prompt, user_prompt, timeout, &mut |_| {})
+    }
+}
+
+/// How often a running generation checks whether Ctrl-C was pressed
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(50);
+static GENERATING: AtomicBool = AtomicBool::new(false);
+static CANCELLED: AtomicBool = AtomicBool::new(false);
+
+/// Marks a running generation. While it is alive the first Ctrl-C cancels the generation
+/// instead of killing the process; a second Ctrl-C quits as usual.
+pub struct Generation;
+
+impl Generation {
+    pub fn start() -> Self {
+        static HANDLER: Once = Once::new();
+        HANDLER.call_once(|| {
+            let _ = ctrlc::set_handler(|| {
+                if GENERATING.load(Ordering::SeqCst) && !CANCELLED.swap(true, Ordering::SeqCst) {
+                    eprintln!("\n⏹️  Cancelling AI generation (press Ctrl-C again to quit)...");
+                } else {
+                    std::process::exit(130);
+                }
+            });
+        });
+
+        CANCELLED.store(false, Ordering::SeqCst);
+        GENERATING.store(true, Ordering::SeqCst);
+        Generation
+    }
+
+    pub fn check_cancelled() -> Result<()> {
+        if CANCELLED.load(Ordering::SeqCst) {
+            anyhow::bail!("AI generation cancelled");
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Generation {
+    fn drop(&mut self) {
+        GENERATING.store(false, Ordering::SeqCst);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderKind {
+    Ollama,
+    OpenAi,
+    Command,
+}
+
+impl ProviderKind {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "ollama" => Ok(Self::Ollama),
+            "openai" | "openai-compatible" => Ok(Self::OpenAi),
+            "command" | "exec" => Ok(Self::Command),
+            other => {
+                anyhow::bail!("Unknown AI provider '{other}'. Use 'ollama', 'openai' or 'command'.")
+            }
+        }
+    }
+}
+
+/// How `--ai-summarize` produces the summary.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SummarizerKind {
+    /// The AI provider, or the built-in summarizer when the provider cannot be reached
+    #[default]
+    Auto,
+    /// Only the AI provider
+    Ai,
+    /// Only the built-in extractive summarizer, no model needed
+    Builtin,
+}
+
+impl SummarizerKind {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "ai" => Ok(Self::Ai),
+            "builtin" | "built-in" => Ok(Self::Builtin),
+            other => {
+                anyhow::bail!("Unknown summarizer '{other}'. Use 'auto', 'ai' or 'builtin'.")
+            }
+        }
+    }
+}
+
+/// Resolved AI settings, see `GitBranchDescManager::ai_settings` for where they come from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AiSettings {
+    pub provider: ProviderKind,
+    pub summarizer: SummarizerKind,
+    pub endpoint: Option<String>,
+    pub model: Option<String>,
+    pub temperature: Option<f64>,
+    pub api_key: Option<String>,
+    pub command: Option<String>,
+    pub chunk_tokens: usize,
+    /// Name of the prompt template, the built-in prompts when `None`
+    pub prompt: Option<String>,
+    pub language: Option<String>,
+    pub length: Option<String>,
+    pub prompts: BTreeMap<String, PromptTemplate>,
+}
+
+/// Settings given on the command line, they take precedence over all configured values.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AiOverrides {
+    pub prompt: Option<String>,
+    pub summarizer: Option<SummarizerKind>,
+    /// Regenerate summaries instead of reusing cached ones
+    pub no_cache: bool,
+}
+
+/// Values for the placeholders of a prompt template.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PromptVars {
+    pub branch: String,
+    pub issue_title: Option<String>,
+}
+
+impl AiSettings {
+    /// Renders the template called `name` when the config defines one, `builtin` otherwise.
+    /// Used for prompts with a fixed purpose such as `mr-text` and `commit-msg`.
+    pub fn named_prompt(&self, name: &str, builtin: &str, vars: &PromptVars) -> String {
+        match self.prompts.get(name) {
+            Some(template) => self.render(&template.system, Some(template), vars),
+            None => self.render(builtin, None, vars),
+        }
+    }
+
+    fn render(&self, text: &str, template: Option<&PromptTemplate>, vars: &PromptVars) -> String {
+        let language = template
+            .and_then(|t| t.language.as_ref())
+            .or(self.language.as_ref());
+        let length = template
+            .and_then(|t| t.length.as_ref())
+            .or(self.length.as_ref());
+
+        render_prompt(
+            text,
+            &[
+                ("branch", vars.branch.as_str()),
+                ("issue_title", vars.issue_title.as_deref().unwrap_or("")),
+                (
+                    "language",
+                    language.map_or(DEFAULT_LANGUAGE, String::as_str),
+                ),
+                ("length", length.map_or(DEFAULT_LENGTH, String::as_str)),
+            ],
+        )
+    }
+
+    /// Renders the system prompt of the selected template. A `default` template in the
+    /// config replaces the built-in prompts.
+    pub fn system_prompt(&self, is_diff: bool, vars: &PromptVars) -> Result<String> {
+        let template = match self.prompt.as_deref() {
+            Some(name) if name != DEFAULT_PROMPT_NAME => {
+                Some(self.prompts.get(name).with_context(|| {
+                    let mut available = vec![DEFAULT_PROMPT_NAME];
+                    available.extend(self.prompts.keys().map(String::as_str));
+                    format!(
+                        "Unknown prompt '{name}'. Available prompts: {}",
+                        available.join(", ")
+                    )
+                })?)
+            }
+            _ => self.prompts.get(DEFAULT_PROMPT_NAME),
+        };
+
+        Ok(match template {
+            Some(template) => {
+                let text = match (&template.diff, is_diff) {
+                    (Some(diff), true) => diff,
+                    _ => &template.system,
+                };
+                self.render(text, Some(template), vars)
+            }
+            None if is_diff => self.render(DEFAULT_DIFF_PROMPT, None, vars),
+            None => self.render(DEFAULT_TEXT_PROMPT, None, vars),
+        })
+    }
+
+    pub fn create_provider(&self) -> Result<Box<dyn AiProvider>> {
+        Ok(match self.provider {
+            ProviderKind::Ollama => Box::new(OllamaProvider {
+                endpoint: self
+                    .endpoint
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_OLLAMA_ENDPOINT.to_string()),
+                model: self
+                    .model
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_OLLAMA_MODEL.to_string()),
+                temperature: self.temperature,
+            }),
+            ProviderKind::OpenAi => Box::new(OpenAiProvider {
+                endpoint: self
+                    .endpoint
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_OPENAI_ENDPOINT.to_string()),
+                model: self.model.clone(),
+                temperature: self.temperature,
+                api_key: self.api_key.clone(),
+            }),
+            ProviderKind::Command => Box::new(CommandProvider {
+                command: self
+                    .command
+                    .clone()
+                    .context("The command AI provider requires branchdesc.ai.command")?,
+            }),
+        })
+    }
+}
+
+/// Ollama's native chat API (`/api/chat`).
+pub struct OllamaProvider {
+    pub endpoint: String,
+    pub model: String,
+    pub temperature: Option<f64>,
+}
+
+impl AiProvider for OllamaProvider {
+    fn name(&self) -> String {
+        format!("Ollama ({})", self.model)
+    }
+
+    fn chat_stream(
+        &self,
+        system_prompt: &str,
+        user_prompt: &str,
+        timeout: Duration,
+        on_token: &mut dyn FnMut(&str),
+    ) -> Result<String> {
+        let mut request_body = serde_json::json!({
+            "model": self.model,
+            "stream": true,
+            "messages": chat_messages(system_prompt, user_prompt),
+        });
+        if let Some(temperature) = self.temperature {
+            request_body["options"] = serde_json::json!({ "temperature": temperature });
+        }
+
+        let url = endpoint_url(&self.endpoint, "/api/chat");
+        let request = Client::new()
+            .post(&url)
+            .timeout(timeout)
+            .json(&request_body);
+        let response = run_cancellable(move || request.send())?.with_context(|| {
+            format!("Failed to connect to Ollama at {url}. Make sure Ollama is running.")
+        })?;
+
+        if !response.status().is_success() {
+            anyhow::bail!(
+                "Ollama API request failed with status: {}",
+                response.status()
+            );
+        }
+
+        // One JSON object per line; a non-streaming reply is a single line as well
+        let mut content = String::new();
+        for line in lines_cancellable(response, "Failed to read Ollama response") {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (token, done) = parse_ollama_stream_line(&line)?;
+            on_token(&token);
+            content.push_str(&token);
+            if done {
+                break;
+            }
+        }
+
+        Ok(content)
+    }
+}
+
+/// OpenAI-compatible chat completions (`/v1/chat/completions`) as served by llama.cpp,
+/// vLLM, LM Studio and hosted APIs.
+pub struct OpenAiProvider {
+    pub endpoint: String,
+    pub model: Option<String>,
+    pub temperature: Option<f64>,
+    pub api_key: Option<String>,
+}
+
+impl AiProvider for OpenAiProvider {
+    fn name(&self) -> String {
+        match &self.model {
+            Some(model) => format!("OpenAI-compatible API ({model})"),
+            None => "OpenAI-compatible API".to_string(),
+        }
+    }
+
+    fn chat_stream(
+        &self,
+        system_prompt: &str,
+        user_prompt: &str,
+        timeout: Duration,
+        on_token: &mut dyn FnMut(&str),
+    ) -> Result<String> {
+        let mut request_body = serde_json::json!({
+            "stream": true,
+            "messages": chat_messages(system_prompt, user_prompt),
+        });
+        if let Some(model) = &self.model {
+            request_body["model"] = Value::from(model.as_str());
+        }
+        if let Some(temperature) = self.temperature {
+            request_body["temperature"] = Value::from(temperature);
+        }
+
+        let url = endpoint_url(&self.endpoint, "/chat/completions");
+        let mut request = Client::new()
+            .post(&url)
+            .timeout(timeout)
+            .json(&request_body);
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = run_cancellable(move || request.send())?
+            .with_context(|| format!("Failed to connect to AI endpoint {url}"))?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("AI API request failed with status: {}", response.status());
+        }
+
+        // Servers without streaming support answer with a single JSON document
+        let is_event_stream = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("text/event-stream"));
+        if !is_event_stream {
+            let response_json: Value = run_cancellable(move || response.json())?
+                .context("Failed to parse AI response as JSON")?;
+            let content = response_json["choices"][0]["message"]["content"]
+                .as_str()
+                .context("Failed to extract content from AI response")?;
+            on_token(content);
+            return Ok(content.to_string());
+        }
+
+        let mut content = String::new();
+        for line in lines_cancellable(response, "Failed to read AI response") {
+            let line = line?;
+            match parse_openai_stream_line(&line)? {
+                OpenAiStreamEvent::Token(token) => {
+                    on_token(&token);
+                    content.push_str(&token);
+                }
+                OpenAiStreamEvent::Done => break,
+                OpenAiStreamEvent::Other => {}
+            }
+        }
+
+        Ok(content)
+    }
+}
+
+/// Runs a shell command with the prompt on stdin and uses its stdout as the completion,
+/// e.g. `llm -m gpt-4o` or `gemini -p`.
+pub struct CommandProvider {
+    pub command: String,
+}
+
+impl AiProvider for CommandProvider {
+    fn name(&self) -> String {
+        format!("'{}'", self.command)
+    }
+
+    fn chat_stream(
+        &self,
+        system_prompt: &str,
+        user_prompt: &str,
+        timeout: Duration,
+        on_token: &mut dyn FnMut(&str),
+    ) -> Result<String> {
+        let mut child = shell_command(&self.command)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to run AI command '{}'", self.command))?;
+
+        // Write the prompt from a thread so a command that streams output early can't
+        // deadlock on a full pipe
+        let mut stdin = child
+            .stdin
+            .take()
+            .context("Failed to open AI command stdin")?;
+        let prompt = format!("{system_prompt}\n\n{user_prompt}");
+        let writer = std::thread::spawn(move || stdin.write_all(prompt.as_bytes()));
+
+        let (sender, receiver) = mpsc::channel();
+        let mut stdout = BufReader::new(
+            child
+                .stdout
+                .take()
+                .context("Failed to open AI command stdout")?,
+        );
+        std::thread::spawn(move || {
+            let mut line = Vec::new();
+            while matches!(stdout.read_until(b'\n', &mut line), Ok(n) if n > 0) {
+                if sender.send(std::mem::take(&mut line)).is_err() {
+                    break;
+                }
+            }
+        });
+        let stderr = read_in_background(child.stderr.take());
+
+        let started = Instant::now();
+        let mut output = Vec::new();
+        loop {
+            match receiver.recv_timeout(CANCEL_POLL_INTERVAL) {
+                Ok(line) => {
+                    on_token(&String::from_utf8_lossy(&line));
+                    output.extend(line);
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+            }
+
+            if let Err(e) = Generation::check_cancelled() {
+                let _ = child.kill();
+                return Err(e);
+            }
+            if started.elapsed() > timeout {
+                let _ = child.kill();
+                anyhow::bail!(
+                    "AI command '{}' timed out after {}s",
+                    self.command,
+                    timeout.as_secs()
+                );
+            }
+        }
+        let status = child.wait()?;
+
+        // A command that ignores its input closes stdin early, that's fine
+        let _ = writer.join();
+
+        if !status.success() {
+            let stderr = stderr.join().unwrap_or_default();
+            anyhow::bail!(
+                "AI command '{}' failed: {}",
+                self.command,
+                String::from_utf8_lossy(&stderr).trim()
+            );
+        }
+
+        Ok(String::from_utf8_lossy(&output).to_string())
+    }
+}
+
+/// Runs `work` on a background thread and waits for it while checking for Ctrl-C, so that a
+/// request can be abandoned while the model is still thinking. An abandoned request ends on
+/// its own at the request timeout.
+fn run_cancellable<T: Send + 'static>(work: impl FnOnce() -> T + Send + 'static) -> Result<T> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = sender.send(work());
+    });
+
+    loop {
+        Generation::check_cancelled()?;
+        match receiver.recv_timeout(CANCEL_POLL_INTERVAL) {
+            Ok(result) => return Ok(result),
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                anyhow::bail!("AI request stopped unexpectedly")
+            }
+        }
+    }
+}
+
+/// The lines of a streamed response, read on a background thread so that Ctrl-C is noticed
+/// while waiting for the next token, not only once it arrives.
+fn lines_cancellable(
+    reader: impl Read + Send + 'static,
+    read_error: &'static str,
+) -> impl Iterator<Item = Result<String>> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in BufReader::new(reader).lines() {
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    std::iter::from_fn(move || {
+        loop {
+            if let Err(e) = Generation::check_cancelled() {
+                return Some(Err(e));
+            }
+            match receiver.recv_timeout(CANCEL_POLL_INTERVAL) {
+                Ok(line) => return Some(line.context(read_error)),
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => return None,
+            }
+        }
+    })
+}
+
+fn read_in_background(
+    pipe: Option<impl Read + Send + 'static>,
+) -> std::thread::JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut output = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut output);
+        }
+        output
+    })
+}
+
+/// Parses one line of Ollama's NDJSON stream into the text it carries and whether the
+/// generation is done.
+pub fn parse_ollama_stream_line(line: &str) -> Result<(String, bool)> {
+    let json: Value = serde_json::from_str(line).context("Failed to parse Ollama response")?;
+    if let Some(error) = json["error"].as_str() {
+        anyhow::bail!("Ollama error: {error}");
+    }
+    let token = json["message"]["content"].as_str().unwrap_or_default();
+    Ok((token.to_string(), json["done"].as_bool().unwrap_or(false)))
+}
+
+#[derive(Debug, PartialEq)]
+pub enum OpenAiStreamEvent {
+    Token(String),
+    Done,
+    /// Comments, keep-alives and chunks without text
+    Other,
+}
+
+/// Parses one line of an OpenAI-style server-sent event stream.
+pub fn parse_openai_stream_line(line: &str) -> Result<OpenAiStreamEvent> {
+    let Some(data) = line.strip_prefix("data:").map(str::trim) else {
+        return Ok(OpenAiStreamEvent::Other);
+    };
+    if data == "[DONE]" {
+        return Ok(OpenAiStreamEvent::Done);
+    }
+    let json: Value = serde_json::from_str(data).context("Failed to parse AI response")?;
+    if let Some(error) = json["error"]["message"].as_str() {
+        anyhow::bail!("AI API error: {error}");
+    }
+    Ok(match json["choices"][0]["delta"]["content"].as_str() {
+        Some(token) if !token.is_empty() => OpenAiStreamEvent::Token(token.to_string()),
+        _ => OpenAiStreamEvent::Other,
+    })
+}
+
+/// Summarizes `content` with `system_prompt`. Content above `chunk_tokens` is split into
+/// parts (per file and hunk for diffs, per section and paragraph otherwise) that are
+/// summarized one by one; the summaries are then summarized again until they fit. Only the
+/// final answer is streamed to `on_token`.
+pub fn summarize(
+    provider: &dyn AiProvider,
+    content: &str,
+    system_prompt: &str,
+    chunk_tokens: usize,
+    timeout: Duration,
+    on_token: &mut dyn FnMut(&str),
+) -> Result<String> {
+    let _generation = Generation::start();
+
+    let tokens = estimate_tokens(content);
+    if tokens <= chunk_tokens {
+        return provider.chat_stream(
+            system_prompt,
+            &format!("Content to summarize:\n\n{content}"),
+            timeout,
+            on_token,
+        );
+    }
+
+    let chunk_prompt = if is_git_diff(content) {
+        CHUNK_DIFF_PROMPT
+    } else {
+        CHUNK_TEXT_PROMPT
+    };
+
+    let mut parts = split_into_chunks(content, chunk_tokens);
+    eprintln!(
+        "📏 Content is ~{tokens} tokens, above the budget of {chunk_tokens}. Summarizing {} parts first...",
+        parts.len()
+    );
+
+    loop {
+        let mut summaries = Vec::new();
+        for (i, part) in parts.iter().enumerate() {
+            eprintln!(
+                "   [{}/{}] Summarizing part (~{} tokens)...",
+                i + 1,
+                parts.len(),
+                estimate_tokens(part)
+            );
+            Generation::check_cancelled()?;
+            let summary = provider.chat(
+                chunk_prompt,
+                &format!("Part {} of {}:\n\n{part}", i + 1, parts.len()),
+                timeout,
+            )?;
+            summaries.push(crate::clean_ai_preamble(&summary));
+        }
+
+        let combined = summaries.join("\n\n");
+        let combined_tokens = estimate_tokens(&combined);
+        if combined_tokens <= chunk_tokens {
+            return provider.chat_stream(
+                system_prompt,
+                &format!("Summaries of the parts of the content:\n\n{combined}"),
+                timeout,
+                on_token,
+            );
+        }
+
+        let next_parts = split_into_chunks(&combined, chunk_tokens);
+        if next_parts.len() >= parts.len() {
+            anyhow::bail!(
+                "AI summaries of the parts do not fit the token budget of {chunk_tokens}. Increase branchdesc.ai.chunkTokens."
+            );
+        }
+        eprintln!(
+            "📏 Summaries are still ~{combined_tokens} tokens, combining them in {} parts...",
+            next_parts.len()
+        );
+        parts = next_parts;
+    }
+}
+
+/// Replaces `{name}` placeholders; unknown placeholders are kept as they are.
+pub fn render_prompt(template: &str, vars: &[(&str, &str)]) -> String {
+    let mut prompt = template.to_string();
+    for (name, value) in vars {
+        prompt = prompt.replace(&format!("{{{name}}}"), value);
+    }
+    prompt
+}
+
+/// Rough token estimate, about 4 characters per token for English text and code.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+pub fn is_git_diff(content: &str) -> bool {
+    content.contains("diff --git")
+        || content.contains("@@")
+        || content
+            .lines()
+            .any(|line| line.starts_with("+++") || line.starts_with("---"))
+}
+
+/// Whether generation failed because the provider is not there at all (server down, command
+/// missing) rather than because of a bad request or a timeout.
+pub fn is_unreachable(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        cause
+            .downcast_ref::<reqwest::Error>()
+            .is_some_and(|err| err.is_connect())
+            || cause
+                .downcast_ref::<std::io::Error>()
+                .is_some_and(|err| err.kind() == std::io::ErrorKind::NotFound)
+    })
+}
+
+/// Splits content into chunks of at most `max_tokens` (estimated), cutting diffs at file
+/// and hunk boundaries and other text at paragraphs. Never cuts inside a character.
+pub fn split_into_chunks(content: &str, max_tokens: usize) -> Vec<String> {
+    let max_chars = max_tokens.max(1) * 4;
+    let pieces = if content.contains("diff --git") {
+        split_diff(content, max_chars)
+    } else {
+        split_paragraphs(content, max_chars)
+    };
+
+    let mut chunks: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut current_chars = 0;
+    for piece in pieces {
+        let piece_chars = piece.chars().count();
+        if current_chars + piece_chars > max_chars && !current.trim().is_empty() {
+            chunks.push(std::mem::take(&mut current));
+            current_chars = 0;
+        }
+        current.push_str(&piece);
+        current_chars += piece_chars;
+    }
+    if !current.trim().is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// One piece per file; files above the budget are split per hunk with the file header
+/// repeated so every piece says which file it belongs to.
+fn split_diff(diff: &str, max_chars: usize) -> Vec<String> {
+    let mut files: Vec<String> = Vec::new();
+    for line in diff.split_inclusive('\n') {
+        if line.starts_with("diff --git ") || files.is_empty() {
+            files.push(String::new());
+        }
+        files.last_mut().unwrap().push_str(line);
+    }
+
+    let mut pieces = Vec::new();
+    for file in files {
+        if file.chars().count() <= max_chars {
+            pieces.push(file);
+            continue;
+        }
+
+        let mut header = String::new();
+        let mut hunks: Vec<String> = Vec::new();
+        for line in file.split_inclusive('\n') {
+            if line.starts_with("@@") {
+                hunks.push(String::new());
+            }
+            match hunks.last_mut() {
+                Some(hunk) => hunk.push_str(line),
+                None => header.push_str(line),
+            }
+        }
+
+        let header_chars = header.chars().count();
+        if header_chars * 2 > max_chars {
+            pieces.extend(split_lines(&file, max_chars));
+            continue;
+        }
+        for hunk in hunks {
+            for part in split_lines(&hunk, max_chars - header_chars) {
+                pieces.push(format!("{header}{part}"));
+            }
+        }
+    }
+    pieces
+}
+
+/// One piece per paragraph, paragraphs above the budget are split by lines.
+fn split_paragraphs(text: &str, max_chars: usize) -> Vec<String> {
+    let mut paragraphs: Vec<String> = vec![String::new()];
+    for line in text.split_inclusive('\n') {
+        paragraphs.last_mut().unwrap().push_str(line);
+        if line.trim().is_empty() {
+            paragraphs.push(String::new());
+        }
+    }
+
+    paragraphs
+        .into_iter()
+        .filter(|p| !p.is_empty())
+        .flat_map(|p| {
+            if p.chars().count() <= max_chars {
+                vec![p]
+            } else {
+                split_lines(&p, max_chars)
+            }
+        })
+        .collect()
+}
+
+/// Groups lines into pieces of at most `max_chars`, splitting overlong lines at character
+/// boundaries.
+fn split_lines(text: &str, max_chars: usize) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+    let mut current_chars = 0;
+    for line in text.split_inclusive('\n') {
+        let chars: Vec<char> = line.chars().collect();
+        for segment in chars.chunks(max_chars.max(1)) {
+            if current_chars + segment.len() > max_chars && !current.is_empty() {
+                pieces.push(std::mem::take(&mut current));
+                current_chars = 0;
+            }
+            current.extend(segment);
+            current_chars += segment.len();
+        }
+    }
+    if !current.is_empty() {
+        pieces.push(current);
+    }
+    pieces
+}
+
+fn chat_messages(system_prompt: &str, user_prompt: &str) -> Value {
+    serde_json::json!([
+        { "role": "system", "content": system_prompt },
+        { "role": "user", "content": user_prompt },
+    ])
+}
+
+/// Accepts both a base URL ("http://host:11434") and the full API URL.
+fn endpoint_url(endpoint: &str, path: &str) -> String {
+    let endpoint = endpoint.trim_end_matches('/');
+    if endpoint.ends_with(path) {
+        endpoint.to_string()
+    } else {
+        format!("{endpoint}{path}")
+    }
+}
+
+fn shell_command(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    #[test]
+    fn test_provider_kind_parse() {
+        assert_eq!(ProviderKind::parse("Ollama").unwrap(), ProviderKind::Ollama);
+        assert_eq!(ProviderKind::parse("openai").unwrap(), ProviderKind::OpenAi);
+        assert_eq!(
+            ProviderKind::parse("command").unwrap(),
+            ProviderKind::Command
+        );
+        assert!(ProviderKind::parse("gpt").is_err());
+
+        assert_eq!(
+            SummarizerKind::parse("builtin").unwrap(),
+            SummarizerKind::Builtin
+        );
+        assert!(SummarizerKind::parse("extractive").is_err());
+    }
+
+    #[test]
+    fn test_endpoint_url() {
+        assert_eq!(
+            endpoint_url("http://localhost:11434/", "/api/chat"),
+            "http://localhost:11434/api/chat"
+        );
+        assert_eq!(
+            endpoint_url("http://gpu:8000/v1/chat/completions", "/chat/completions"),
+            "http://gpu:8000/v1/chat/completions"
+        );
+    }
+
+    /// Answers every request with a short fixed text and records the prompts.
+    struct RecordingProvider {
+        prompts: std::cell::RefCell<Vec<String>>,
+    }
+
+    impl AiProvider for RecordingProvider {
+        fn name(&self) -> String {
+            "recording".to_string()
+        }
+
+        fn chat_stream(
+            &self,
+            _system: &str,
+            user_prompt: &str,
+            _timeout: Duration,
+            on_token: &mut dyn FnMut(&str),
+        ) -> Result<String> {
+            self.prompts.borrow_mut().push(user_prompt.to_string());
+            let summary = format!("summary {}", self.prompts.borrow().len());
+            on_token(&summary);
+            Ok(summary)
+        }
+    }
+
+    fn settings_with_prompts(config: &str) -> AiSettings {
+        let file = crate::config::FileConfig::parse(config).unwrap();
+        AiSettings {
+            provider: ProviderKind::Ollama,
+            summarizer: SummarizerKind::Auto,
+            endpoint: None,
+            model: None,
+            temperature: None,
+            api_key: None,
+            command: None,
+            chunk_tokens: DEFAULT_CHUNK_TOKENS,
+            prompt: file.ai.prompt,
+            language: file.ai.language,
+            length: file.ai.length,
+            prompts: file.prompts,
+        }
+    }
+
+    #[test]
+    fn test_system_prompt_templates() {
+        let vars = PromptVars {
+            branch: "feature/42-export".to_string(),
+            issue_title: Some("CSV export".to_string()),
+        };
+
+        let settings = settings_with_prompts("");
+        let prompt = settings.system_prompt(false, &vars).unwrap();
+        assert!(prompt.contains("of 2-3 sentences in English"));
+
+        let mut settings = settings_with_prompts(
+            r#"
+[ai]
+language = "German"
+
+[prompts.bullets]
+system = "Branch {branch} ({issue_title}): {length} bullet points in {language}. {unknown}"
+diff = "Diff of {branch}"
+length = "3"
+"#,
+        );
+        assert!(
+            settings
+                .system_prompt(false, &vars)
+                .unwrap()
+                .contains("in German")
+        );
+
+        settings.prompt = Some("bullets".to_string());
+        assert_eq!(
+            settings.system_prompt(false, &vars).unwrap(),
+            "Branch feature/42-export (CSV export): 3 bullet points in German. {unknown}"
+        );
+        assert_eq!(
+            settings.system_prompt(true, &vars).unwrap(),
+            "Diff of feature/42-export"
+        );
+
+        settings.prompt = Some("missing".to_string());
+        let error = settings.system_prompt(false, &vars).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("Available prompts: default, bullets")
+        );
+    }
+
+    #[test]
+    fn test_estimate_tokens() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abcd"), 1);
+        assert_eq!(estimate_tokens("abcde"), 2);
+        assert_eq!(estimate_tokens("äöüß"), 1);
+    }
+
+    #[test]
+    fn test_split_diff_per_file_and_hunk() {
+        let file_a = "diff --git a/a.rs b/a.rs\n--- a/a.rs\n+++ b/a.rs\n@@ -1 +1 @@\n-old\n+new\n";
+        let big_hunk = format!("@@ -10 +10 @@\n{}", "+added line\n".repeat(20));
+        let file_b =
+            format!("diff --git a/b.rs b/b.rs\n--- a/b.rs\n+++ b/b.rs\n{big_hunk}{big_hunk}");
+        let diff = format!("{file_a}{file_b}");
+
+        let chunks = split_into_chunks(&diff, 80);
+        assert!(chunks.len() >= 3);
+        assert!(chunks[0].starts_with("diff --git a/a.rs"));
+        for chunk in &chunks {
+            assert!(estimate_tokens(chunk) <= 80);
+            assert!(chunk.starts_with("diff --git"));
+        }
+        // Hunks of the big file repeat its header
+        assert!(
+            chunks[1..]
+                .iter()
+                .all(|c| c.starts_with("diff --git a/b.rs"))
+        );
+    }
+
+    #[test]
+    fn test_split_text_keeps_characters_intact() {
+        let text = format!("{}\n\n{}", "ä".repeat(50), "Second paragraph");
+        let chunks = split_into_chunks(&text, 5);
+        assert_eq!(chunks.concat(), text);
+        assert!(chunks.iter().all(|c| c.chars().count() <= 20));
+
+        // Small paragraphs are packed together
+        assert_eq!(
+            split_into_chunks("One.\n\nTwo.\n", 100),
+            vec!["One.\n\nTwo.\n"]
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_summarize_map_reduce() {
+        let provider = RecordingProvider {
+            prompts: Default::default(),
+        };
+        let mut streamed = String::new();
+        let short = summarize(
+            &provider,
+            "short text",
+            "system",
+            100,
+            Duration::from_secs(1),
+            &mut |token| streamed.push_str(token),
+        );
+        assert_eq!(short.unwrap(), "summary 1");
+        assert_eq!(streamed, "summary 1");
+
+        let provider = RecordingProvider {
+            prompts: Default::default(),
+        };
+        let long = format!(
+            "{}\n\n{}\n\n{}",
+            "a".repeat(300),
+            "b".repeat(300),
+            "c".repeat(300)
+        );
+        let mut streamed = String::new();
+        let summary = summarize(
+            &provider,
+            &long,
+            "system",
+            100,
+            Duration::from_secs(1),
+            &mut |token| streamed.push_str(token),
+        )
+        .unwrap();
+
+        // Three parts, then one request over their summaries
+        let prompts = provider.prompts.borrow();
+        assert_eq!(prompts.len(), 4);
+        assert!(prompts[0].starts_with("Part 1 of 3"));
+        assert!(prompts[3].contains("summary 1\n\nsummary 2\n\nsummary 3"));
+        assert_eq!(summary, "summary 4");
+        // Only the final answer is streamed
+        assert_eq!(streamed, "summary 4");
+    }
+
+    #[test]
+    fn test_parse_ollama_stream_line() {
+        assert_eq!(
+            parse_ollama_stream_line(
+                r#"{"message":{"role":"assistant","content":"Add"},"done":false}"#
+            )
+            .unwrap(),
+            ("Add".to_string(), false)
+        );
+        assert_eq!(
+            parse_ollama_stream_line(r#"{"message":{"content":""},"done":true}"#).unwrap(),
+            (String::new(), true)
+        );
+        assert!(parse_ollama_stream_line(r#"{"error":"model not found"}"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_openai_stream_line() {
+        assert_eq!(
+            parse_openai_stream_line(r#"data: {"choices":[{"delta":{"content":"Fix"}}]}"#).unwrap(),
+            OpenAiStreamEvent::Token("Fix".to_string())
+        );
+        assert_eq!(
+            parse_openai_stream_line("data: [DONE]").unwrap(),
+            OpenAiStreamEvent::Done
+        );
+        assert_eq!(
+            parse_openai_stream_line(": keep-alive").unwrap(),
+            OpenAiStreamEvent::Other
+        );
+        assert_eq!(
+            parse_openai_stream_line(r#"data: {"choices":[{"delta":{"role":"assistant"}}]}"#)
+                .unwrap(),
+            OpenAiStreamEvent::Other
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    #[serial]
+    fn test_command_provider() {
+        let provider = CommandProvider {
+            command: "tr a-z A-Z | tail -n 1".to_string(),
+        };
+        let output = provider
+            .chat("system", "user prompt", Duration::from_secs(10))
+            .unwrap();
+        assert_eq!(output.trim(), "USER PROMPT");
+
+        let failing = CommandProvider {
+            command: "echo broken >&2; exit 3".to_string(),
+        };
+        let error = failing
+            .chat("system", "user", Duration::from_secs(10))
+            .unwrap_err();
+        assert!(error.to_string().contains("broken"));
+    }
+
+    /// An endpoint that accepts the request and then sends `reply` and nothing more.
+    fn stalled_endpoint(reply: &'static str) -> String {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            if let Ok((mut stream, _)) = listener.accept() {
+                // Take the whole request first, so the client sees the reply as an answer
+                let _ = stream.set_read_timeout(Some(Duration::from_millis(50)));
+                let _ = std::io::copy(&mut stream, &mut std::io::sink());
+                let _ = stream.write_all(reply.as_bytes());
+                std::thread::sleep(Duration::from_secs(30));
+            }
+        });
+        format!("http://{address}")
+    }
+
+    #[test]
+    #[serial]
+    fn test_cancel_while_waiting_for_the_model() {
+        let no_response = stalled_endpoint("");
+        let no_first_token = stalled_endpoint(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/x-ndjson\r\nTransfer-Encoding: chunked\r\n\r\n",
+        );
+
+        for endpoint in [no_response, no_first_token] {
+            let provider = OllamaProvider {
+                endpoint,
+                model: "test".to_string(),
+                temperature: None,
+            };
+            let _generation = Generation::start();
+            // Ctrl-C while the request is in flight
+            let ctrl_c = std::thread::spawn(|| {
+                std::thread::sleep(Duration::from_millis(100));
+                CANCELLED.store(true, Ordering::SeqCst);
+            });
+
+            let started = Instant::now();
+            let result = provider.chat("system", "user", Duration::from_secs(30));
+            ctrl_c.join().unwrap();
+            CANCELLED.store(false, Ordering::SeqCst);
+
+            let err = result.unwrap_err();
+            assert_eq!(format!("{err:#}"), "AI generation cancelled");
+            assert!(started.elapsed() < Duration::from_secs(5));
+        }
+    }
+}
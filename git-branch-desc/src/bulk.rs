@@ -0,0 +1,232 @@
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::fmt;
+
+/// Starts a branch section in the `bulk` editor buffer, like the headers of `head a b`
+const SECTION_PREFIX: &str = "==> ";
+const SECTION_SUFFIX: &str = " <==";
+
+/// A branch and the description it should get.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BulkEntry {
+    pub branch: String,
+    pub description: String,
+}
+
+impl BulkEntry {
+    pub fn new(branch: &str, description: &str) -> Self {
+        Self {
+            branch: branch.trim().to_string(),
+            description: description.trim().to_string(),
+        }
+    }
+}
+
+/// What happened to one branch of a bulk edit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BulkOutcome {
+    /// Description empty or the same as before, nothing committed
+    Skipped,
+    /// Committed locally, not pushed
+    Committed,
+    Pushed,
+    Failed(String),
+}
+
+impl fmt::Display for BulkOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Skipped => write!(f, "⏭️  skipped (unchanged or empty)"),
+            Self::Committed => write!(f, "✅ committed"),
+            Self::Pushed => write!(f, "✅ committed and pushed"),
+            Self::Failed(message) => write!(f, "❌ {message}"),
+        }
+    }
+}
+
+/// Reads `{"branch": "description", ...}` or `[{"branch": ..., "description": ...}, ...]`.
+pub fn parse_json(text: &str) -> Result<Vec<BulkEntry>> {
+    let parsed: Value = serde_json::from_str(text).context("Invalid JSON bulk input")?;
+
+    match parsed {
+        Value::Object(map) => map
+            .iter()
+            .map(|(branch, description)| {
+                let description = description
+                    .as_str()
+                    .with_context(|| format!("Description of '{branch}' must be a string"))?;
+                Ok(BulkEntry::new(branch, description))
+            })
+            .collect(),
+        Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                let field = |name: &str| {
+                    item[name].as_str().with_context(|| {
+                        format!("Entry {} needs a string field '{name}'", index + 1)
+                    })
+                };
+                Ok(BulkEntry::new(field("branch")?, field("description")?))
+            })
+            .collect(),
+        _ => anyhow::bail!("Bulk JSON must be an object or an array of objects"),
+    }
+}
+
+/// Reads `branch,description` rows with an optional header row. Fields may be quoted, quoted
+/// fields can contain commas, newlines and `""` for a quote.
+pub fn parse_csv(text: &str) -> Result<Vec<BulkEntry>> {
+    let mut entries = Vec::new();
+    for (index, record) in parse_csv_records(text)?.into_iter().enumerate() {
+        if record.iter().all(|field| field.trim().is_empty()) {
+            continue;
+        }
+        let [branch, description] = record.as_slice() else {
+            anyhow::bail!(
+                "CSV row {} has {} columns, expected branch,description",
+                index + 1,
+                record.len()
+            );
+        };
+        if index == 0
+            && branch.trim().eq_ignore_ascii_case("branch")
+            && description.trim().eq_ignore_ascii_case("description")
+        {
+            continue;
+        }
+        entries.push(BulkEntry::new(branch, description));
+    }
+    Ok(entries)
+}
+
+fn parse_csv_records(text: &str) -> Result<Vec<Vec<String>>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', true) => in_quotes = false,
+            ('"', false) if field.is_empty() => in_quotes = true,
+            (',', false) => record.push(std::mem::take(&mut field)),
+            ('\r', false) => {}
+            ('\n', false) => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            _ => field.push(c),
+        }
+    }
+    if in_quotes {
+        anyhow::bail!("Unterminated quoted field in CSV bulk input");
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Renders the editor buffer: one `==> branch <==` section per branch.
+pub fn format_editor_buffer(entries: &[BulkEntry]) -> String {
+    entries
+        .iter()
+        .map(|entry| {
+            let mut section = format!("{SECTION_PREFIX}{}{SECTION_SUFFIX}\n", entry.branch);
+            if !entry.description.is_empty() {
+                section.push_str(&entry.description);
+                section.push('\n');
+            }
+            section
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Parses the edited buffer. Text before the first section is ignored.
+pub fn parse_editor_buffer(text: &str) -> Vec<BulkEntry> {
+    let mut entries = Vec::new();
+    let mut current: Option<(&str, Vec<&str>)> = None;
+
+    for line in text.lines() {
+        let header = line
+            .strip_prefix(SECTION_PREFIX)
+            .and_then(|rest| rest.trim_end().strip_suffix(SECTION_SUFFIX));
+        match (header, current.as_mut()) {
+            (Some(branch), _) => {
+                if let Some((branch, lines)) = current.take() {
+                    entries.push(BulkEntry::new(branch, &lines.join("\n")));
+                }
+                current = Some((branch, Vec::new()));
+            }
+            (None, Some((_, lines))) => lines.push(line),
+            (None, None) => {}
+        }
+    }
+    if let Some((branch, lines)) = current {
+        entries.push(BulkEntry::new(branch, &lines.join("\n")));
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_json() {
+        let entries = parse_json(r#"{"feature/a": "First", "feature/b": " Second\n"}"#).unwrap();
+        assert_eq!(
+            entries,
+            [
+                BulkEntry::new("feature/a", "First"),
+                BulkEntry::new("feature/b", "Second")
+            ]
+        );
+
+        let entries = parse_json(r#"[{"branch": "feature/a", "description": "First"}]"#).unwrap();
+        assert_eq!(entries, [BulkEntry::new("feature/a", "First")]);
+
+        assert!(parse_json(r#"[{"branch": "feature/a"}]"#).is_err());
+        assert!(parse_json(r#"{"feature/a": 1}"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let csv = "branch,description\r\nfeature/a,First\nfeature/b,\"Multi\nline, with \"\"quotes\"\"\"\n\n";
+        assert_eq!(
+            parse_csv(csv).unwrap(),
+            [
+                BulkEntry::new("feature/a", "First"),
+                BulkEntry::new("feature/b", "Multi\nline, with \"quotes\"")
+            ]
+        );
+        assert!(parse_csv("feature/a,one,two\n").is_err());
+        assert!(parse_csv("feature/a,\"open\n").is_err());
+    }
+
+    #[test]
+    fn test_editor_buffer_round_trip() {
+        let entries = vec![
+            BulkEntry::new("feature/a", "# Heading\n\nBody"),
+            BulkEntry::new("feature/b", ""),
+        ];
+        let buffer = format_editor_buffer(&entries);
+        assert_eq!(
+            buffer,
+            "==> feature/a <==\n# Heading\n\nBody\n\n==> feature/b <==\n"
+        );
+        assert_eq!(parse_editor_buffer(&buffer), entries);
+        assert_eq!(
+            parse_editor_buffer("ignored\n==> feature/c <==\nNew"),
+            [BulkEntry::new("feature/c", "New")]
+        );
+    }
+}
@@ -0,0 +1,131 @@
+use anyhow::{Context, Result};
+use git2::{ObjectType, Oid, Repository};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+/// Directory below the repository's `.git` directory, shared by its worktrees
+const CACHE_DIR: &str = "branchdesc-cache";
+
+/// AI summaries stored as one file per key, so regenerating descriptions for unchanged
+/// issues and diffs does not wait for the model again.
+pub struct SummaryCache {
+    dir: PathBuf,
+}
+
+/// What `SummaryCache::prune` removed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PruneStats {
+    pub entries: usize,
+    pub bytes: u64,
+}
+
+impl SummaryCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    pub fn for_repo(repo: &Repository) -> Self {
+        Self::new(crate::common_dir(repo).join(CACHE_DIR))
+    }
+
+    /// Hashes the parts (e.g. provider and model, prompt, content) into a cache key. Each part
+    /// is prefixed with its length so that moving text between them changes the key.
+    pub fn key(parts: &[&str]) -> Result<String> {
+        let mut data = Vec::new();
+        for part in parts {
+            data.extend_from_slice(format!("{}:", part.len()).as_bytes());
+            data.extend_from_slice(part.as_bytes());
+        }
+        let oid = Oid::hash_object(ObjectType::Blob, &data)?;
+        Ok(oid.to_string())
+    }
+
+    pub fn get(&self, key: &str) -> Option<String> {
+        fs::read_to_string(self.entry_path(key)).ok()
+    }
+
+    pub fn put(&self, key: &str, summary: &str) -> Result<()> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create cache directory {}", self.dir.display()))?;
+        fs::write(self.entry_path(key), summary).context("Failed to write cached AI summary")
+    }
+
+    /// Removes entries last written more than `max_age` ago, or all entries without one.
+    pub fn prune(&self, max_age: Option<Duration>) -> Result<PruneStats> {
+        let mut stats = PruneStats::default();
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(_) => return Ok(stats),
+        };
+
+        let now = SystemTime::now();
+        for entry in entries {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            if !metadata.is_file() {
+                continue;
+            }
+            let expired = match max_age {
+                Some(max_age) => metadata
+                    .modified()
+                    .ok()
+                    .and_then(|modified| now.duration_since(modified).ok())
+                    .is_some_and(|age| age > max_age),
+                None => true,
+            };
+            if expired {
+                fs::remove_file(entry.path()).with_context(|| {
+                    format!("Failed to remove cache entry {}", entry.path().display())
+                })?;
+                stats.entries += 1;
+                stats.bytes += metadata.len();
+            }
+        }
+        Ok(stats)
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.md"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_summary_cache() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = SummaryCache::new(temp_dir.path().join("cache"));
+
+        let key = SummaryCache::key(&["Ollama (llama3.2:1b)", "prompt", "content"]).unwrap();
+        assert_eq!(key.len(), 40);
+        assert_ne!(
+            SummaryCache::key(&["a", "b\0c"]).unwrap(),
+            SummaryCache::key(&["a", "b", "c"]).unwrap()
+        );
+        assert_ne!(
+            SummaryCache::key(&["ab", "c"]).unwrap(),
+            SummaryCache::key(&["a", "bc"]).unwrap()
+        );
+
+        assert_eq!(cache.get(&key), None);
+        cache.put(&key, "Summary").unwrap();
+        assert_eq!(cache.get(&key).as_deref(), Some("Summary"));
+
+        // Fresh entries survive an age-based prune
+        let stats = cache.prune(Some(Duration::from_secs(3600))).unwrap();
+        assert_eq!(stats.entries, 0);
+        let stats = cache.prune(None).unwrap();
+        assert_eq!(
+            stats,
+            PruneStats {
+                entries: 1,
+                bytes: 7
+            }
+        );
+        assert_eq!(cache.get(&key), None);
+    }
+}
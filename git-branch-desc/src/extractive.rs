@@ -0,0 +1,262 @@
+use std::path::Path;
+
+/// Longest paragraph taken over from the content, cut at a sentence boundary
+const MAX_PARAGRAPH_CHARS: usize = 400;
+/// List items kept when the first meaningful paragraph is a list
+const MAX_LIST_ITEMS: usize = 5;
+/// Modules named in the change summary before the rest is counted
+const MAX_MODULES: usize = 8;
+
+/// Builds a short description without an AI model: the issue title, the first meaningful
+/// paragraph of the text and, for diffs, the list of changed modules.
+pub fn summarize(content: &str, issue_title: Option<&str>) -> String {
+    let (text, diff) = split_text_and_diff(content);
+    let title = issue_title
+        .map(|title| title.trim().trim_start_matches('#').trim())
+        .filter(|title| !title.is_empty());
+
+    let mut parts = Vec::new();
+    if let Some(title) = title {
+        parts.push(title.to_string());
+    }
+    if let Some(paragraph) = first_meaningful_paragraph(&text, title) {
+        parts.push(paragraph);
+    }
+    if let Some(changes) = describe_changes(&diff) {
+        parts.push(changes);
+    }
+    parts.join("\n\n")
+}
+
+/// Separates diff lines from the surrounding text. A diff ends at the next `## ` section
+/// heading, which is how combined input sources are labelled.
+fn split_text_and_diff(content: &str) -> (String, String) {
+    let mut text = String::new();
+    let mut diff = String::new();
+    let mut in_diff = false;
+
+    for line in content.lines() {
+        if line.starts_with("diff --git ") || line.starts_with("--- a/") || line.starts_with("@@ ")
+        {
+            in_diff = true;
+        } else if line.starts_with("## ") {
+            in_diff = false;
+        }
+
+        let target = if in_diff { &mut diff } else { &mut text };
+        target.push_str(line);
+        target.push('\n');
+    }
+    (text, diff)
+}
+
+fn first_meaningful_paragraph(text: &str, title: Option<&str>) -> Option<String> {
+    let mut in_code_block = false;
+    let mut paragraph: Vec<&str> = Vec::new();
+
+    for line in text.lines().chain(std::iter::once("")) {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+
+        let skipped = trimmed.starts_with('#')
+            || trimmed.starts_with("<!--")
+            || trimmed.starts_with('>')
+            || trimmed.starts_with('|')
+            || Some(trimmed) == title;
+        if !trimmed.is_empty() && !skipped {
+            paragraph.push(trimmed);
+            continue;
+        }
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            if let Some(summary) = summarize_paragraph(&paragraph) {
+                return Some(summary);
+            }
+            paragraph.clear();
+        }
+    }
+    None
+}
+
+/// Keeps lists as lists and shortens prose to its first sentences. Paragraphs of a few words
+/// (labels, "N/A", template leftovers) are not meaningful.
+fn summarize_paragraph(lines: &[&str]) -> Option<String> {
+    let words = lines
+        .iter()
+        .map(|line| line.split_whitespace().count())
+        .sum::<usize>();
+    if words < 4 {
+        return None;
+    }
+
+    if lines.first().is_some_and(|line| is_list_item(line)) {
+        let items: Vec<&str> = lines
+            .iter()
+            .copied()
+            .filter(|line| is_list_item(line))
+            .filter(|line| !line.contains("[ ]"))
+            .take(MAX_LIST_ITEMS)
+            .collect();
+        return (!items.is_empty()).then(|| items.join("\n"));
+    }
+
+    Some(truncate_sentences(&lines.join(" "), MAX_PARAGRAPH_CHARS))
+}
+
+fn is_list_item(line: &str) -> bool {
+    line.starts_with("- ")
+        || line.starts_with("* ")
+        || line.split_once(". ").is_some_and(|(number, _)| {
+            !number.is_empty() && number.chars().all(|c| c.is_ascii_digit())
+        })
+}
+
+/// Takes whole sentences up to `max_chars`; a single longer sentence is cut at a word.
+fn truncate_sentences(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+
+    let mut end = 0;
+    for (index, _) in text.match_indices(['.', '!', '?']) {
+        let sentence_end = index + 1;
+        let at_boundary = text[sentence_end..].starts_with(' ') || sentence_end == text.len();
+        if at_boundary && text[..sentence_end].chars().count() <= max_chars {
+            end = sentence_end;
+        }
+    }
+    if end > 0 {
+        return text[..end].to_string();
+    }
+
+    let cut: String = text.chars().take(max_chars).collect();
+    let cut = cut.rsplit_once(' ').map_or(cut.as_str(), |(head, _)| head);
+    format!("{}…", cut.trim_end_matches([',', ';', ':']))
+}
+
+/// One line naming the changed modules with file and line counts, `None` without a diff.
+fn describe_changes(diff: &str) -> Option<String> {
+    let mut files: Vec<String> = Vec::new();
+    let (mut added, mut removed) = (0, 0);
+
+    for line in diff.lines() {
+        if let Some(paths) = line.strip_prefix("diff --git ") {
+            if let Some((_, new_path)) = paths.rsplit_once(" b/") {
+                push_unique(&mut files, new_path);
+            }
+        } else if let Some(path) = line.strip_prefix("+++ b/") {
+            push_unique(&mut files, path);
+        } else if line.starts_with('+') && !line.starts_with("+++") {
+            added += 1;
+        } else if line.starts_with('-') && !line.starts_with("---") {
+            removed += 1;
+        }
+    }
+    files.retain(|file| file != "BRANCHREADME.md");
+    if files.is_empty() {
+        return None;
+    }
+
+    let mut modules: Vec<String> = Vec::new();
+    for file in &files {
+        push_unique(&mut modules, &module_name(file));
+    }
+    let mut names: Vec<String> = modules
+        .iter()
+        .take(MAX_MODULES)
+        .map(|module| format!("`{module}`"))
+        .collect();
+    if modules.len() > MAX_MODULES {
+        names.push(format!("{} more", modules.len() - MAX_MODULES));
+    }
+
+    let file_count = match files.len() {
+        1 => "1 file".to_string(),
+        count => format!("{count} files"),
+    };
+    Some(format!(
+        "Changed modules: {} ({file_count}, +{added}/-{removed}).",
+        names.join(", ")
+    ))
+}
+
+fn push_unique(items: &mut Vec<String>, item: &str) {
+    if !items.iter().any(|existing| existing == item) {
+        items.push(item.to_string());
+    }
+}
+
+/// The directory of a file without `src` components, or the file stem for top-level files:
+/// `src/ai.rs` is `ai`, `crates/core/src/lib.rs` is `crates/core`.
+fn module_name(file: &str) -> String {
+    let path = Path::new(file);
+    let dirs: Vec<String> = path
+        .parent()
+        .into_iter()
+        .flat_map(|parent| parent.components())
+        .map(|component| component.as_os_str().to_string_lossy().into_owned())
+        .filter(|component| component != "src")
+        .collect();
+
+    if dirs.is_empty() {
+        path.file_stem().map_or_else(
+            || file.to_string(),
+            |stem| stem.to_string_lossy().into_owned(),
+        )
+    } else {
+        dirs.join("/")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarize_issue() {
+        let issue = "Add CSV export\n\n## Context\n\n<!-- Describe the problem -->\nN/A\n\nUsers need to export their reports as CSV files. Excel is the main consumer. The export has to run in the background for large reports.\n\n## Tasks\n\n- [ ] Add button\n";
+        assert_eq!(
+            summarize(issue, Some("Add CSV export")),
+            "Add CSV export\n\nUsers need to export their reports as CSV files. Excel is the main consumer. The export has to run in the background for large reports."
+        );
+
+        // Without an issue title the first paragraph is enough
+        assert_eq!(
+            summarize("Fix the login redirect loop on expired sessions.", None),
+            "Fix the login redirect loop on expired sessions."
+        );
+        assert_eq!(summarize("", None), "");
+    }
+
+    #[test]
+    fn test_summarize_diff() {
+        let content = "## Commits\n\n- Add login handler\n  Supports password auth\n- Add logout\n\n## Diff\n\ndiff --git a/src/auth/login.rs b/src/auth/login.rs\n--- a/src/auth/login.rs\n+++ b/src/auth/login.rs\n@@ -1 +1,2 @@\n fn login() {}\n+fn logout() {}\ndiff --git a/README.md b/README.md\n--- a/README.md\n+++ b/README.md\n@@ -1 +1 @@\n-Old\n+New\n";
+        assert_eq!(
+            summarize(content, None),
+            "- Add login handler\n- Add logout\n\nChanged modules: `auth`, `README` (2 files, +2/-1)."
+        );
+    }
+
+    #[test]
+    fn test_truncate_sentences() {
+        let text = "First sentence here. Second one follows. Third is long enough to overflow.";
+        assert_eq!(
+            truncate_sentences(text, 45),
+            "First sentence here. Second one follows."
+        );
+        assert_eq!(truncate_sentences("one two three four", 12), "one two…");
+    }
+
+    #[test]
+    fn test_module_name() {
+        assert_eq!(module_name("src/ai.rs"), "ai");
+        assert_eq!(module_name("README.md"), "README");
+        assert_eq!(module_name("crates/core/src/lib.rs"), "crates/core");
+        assert_eq!(module_name("tests/integration_tests.rs"), "tests");
+    }
+}
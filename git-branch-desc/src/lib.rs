@@ -1,17 +1,43 @@
+use ai::{AiOverrides, AiSettings, PromptVars, ProviderKind, SummarizerKind};
 use anyhow::{Context, Result};
 use arboard::Clipboard;
+use bulk::{BulkEntry, BulkOutcome};
+use cache::SummaryCache;
+use config::FileConfig;
 use git2::Repository;
+use message::{MessageSettings, MessageVars};
 use regex::Regex;
 use reqwest::blocking::Client;
 use serde_json::Value;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
 use std::io::{self, IsTerminal, Read, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::LazyLock;
 use tabwriter::TabWriter;
 use terminal_size::{Width, terminal_size};
 
+pub mod ai;
+pub mod bulk;
+pub mod cache;
+pub mod config;
+pub mod extractive;
+pub mod message;
+pub mod push;
+pub mod signing;
+pub mod tui;
+
+const DEFAULT_GITHUB_API_URL: &str = "https://api.github.com";
+const WATCH_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+/// Holds commits for remote-only branches between `bulk` committing and pushing them
+const BULK_PUSH_REF_PREFIX: &str = "refs/branchdesc/bulk/";
+const EDITOR_SCISSORS_LINE: &str = "# ------------------------ >8 ------------------------";
+
+/// Matches `bugfix/1234`, `feature/1234-login` and `feature/PROJ-123-login`, but not the
+/// versions in `release/1.2.0`.
+const DEFAULT_ISSUE_PATTERN: &str = r"(?:^|/)(\d+|[A-Z][A-Z0-9_]+-\d+)(?:[-_]|$)";
+
 #[derive(Debug, Clone)]
 pub enum InputSource {
     /// Direct command line input (text argument or interactive prompt)
@@ -20,10 +46,85 @@ pub enum InputSource {
     Clipboard,
     /// Read from standard input
     Stdin,
-    /// Read from GitLab issue reference
-    Issue(String),
+    /// Read from a text file (BOM and UTF-16/Windows-1252 encodings are handled)
+    File(PathBuf),
+    /// Read from GitLab issue reference (detected from the branch name when omitted)
+    Issue(Option<String>),
+    /// Read from GitHub issue or pull request reference
+    GitHub(String),
+    /// Read from Jira issue key or URL (detected from the branch name when omitted)
+    Jira(Option<String>),
+    /// Read from GitLab merge request reference (open MR of the target branch when omitted)
+    MergeRequest {
+        reference: Option<String>,
+        include_discussions: bool,
+    },
+    /// Read commit messages since the merge-base with the parent branch
+    Commits,
+    /// Read unified diff since the merge-base with the parent branch
+    Diff,
     /// Open external editor with prefilled template
     Editor,
+    /// Read several sources and merge them as labelled sections
+    Combined(Vec<InputSource>),
+}
+
+impl InputSource {
+    /// Sources whose content starts with an issue or merge request title
+    pub fn is_issue(&self) -> bool {
+        matches!(
+            self,
+            InputSource::Issue(_)
+                | InputSource::GitHub(_)
+                | InputSource::Jira(_)
+                | InputSource::MergeRequest { .. }
+        )
+    }
+
+    /// Rejects direct text for `--ai-summarize`, also as part of combined sources.
+    pub fn validate_for_ai_summarize(&self) -> Result<()> {
+        match self {
+            InputSource::CommandLine(Some(_)) => {
+                anyhow::bail!(
+                    "AI summarization cannot be used with direct text input. Use --input=clipboard, --input=stdin, --input=editor, or --input=issue instead."
+                );
+            }
+            InputSource::Combined(sources) => sources
+                .iter()
+                .try_for_each(InputSource::validate_for_ai_summarize),
+            InputSource::CommandLine(None)
+            | InputSource::Clipboard
+            | InputSource::Stdin
+            | InputSource::File(_)
+            | InputSource::Issue(_)
+            | InputSource::GitHub(_)
+            | InputSource::Jira(_)
+            | InputSource::MergeRequest { .. }
+            | InputSource::Commits
+            | InputSource::Diff
+            | InputSource::Editor => {
+                // Valid combinations
+                Ok(())
+            }
+        }
+    }
+
+    /// Section heading used when this source is part of a combined description
+    pub fn label(&self) -> String {
+        match self {
+            InputSource::CommandLine(_) | InputSource::Editor => "Notes".to_string(),
+            InputSource::Clipboard => "Clipboard".to_string(),
+            InputSource::Stdin => "Input".to_string(),
+            InputSource::File(path) => format!("File {}", path.display()),
+            InputSource::Issue(_) => "Issue".to_string(),
+            InputSource::GitHub(_) => "GitHub issue".to_string(),
+            InputSource::Jira(_) => "Jira issue".to_string(),
+            InputSource::MergeRequest { .. } => "Merge request".to_string(),
+            InputSource::Commits => "Commits".to_string(),
+            InputSource::Diff => "Changes".to_string(),
+            InputSource::Combined(_) => "Sources".to_string(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -34,15 +135,55 @@ pub struct BranchDescription {
 
 pub struct GitBranchDescManager {
     repo: Repository,
+    ai_overrides: AiOverrides,
+    force_with_lease: bool,
+    amend: bool,
+    dry_run: bool,
 }
 
 impl GitBranchDescManager {
+    /// Opens the repository containing `repo_path` like git does: from a subdirectory, a
+    /// linked worktree or a bare repository.
     pub fn new(repo_path: &str) -> Result<Self> {
-        let repo = Repository::open(repo_path)
+        let repo = Repository::discover(repo_path)
             .context("Failed to open repository. Make sure you're in a Git repository.")?;
-        Ok(Self { repo })
+        Ok(Self {
+            repo,
+            ai_overrides: AiOverrides::default(),
+            force_with_lease: false,
+            amend: false,
+            dry_run: false,
+        })
     }
 
+    /// Applies AI settings given on the command line, e.g. `--prompt`.
+    pub fn with_ai_overrides(mut self, ai_overrides: AiOverrides) -> Self {
+        self.ai_overrides = ai_overrides;
+        self
+    }
+
+    /// Overwrites remote branches on push as long as they still point where the
+    /// remote-tracking branch says, like `git push --force-with-lease`.
+    pub fn with_force_with_lease(mut self, force_with_lease: bool) -> Self {
+        self.force_with_lease = force_with_lease;
+        self
+    }
+
+    /// Replaces the branch's tip instead of adding a commit when the tip is an unpushed
+    /// description-only commit, see `amendable_description_commit`.
+    pub fn with_amend(mut self, amend: bool) -> Self {
+        self.amend = amend;
+        self
+    }
+
+    /// Makes `edit_description_v2` print the description and the commit it would create
+    /// instead of writing, committing or pushing anything.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn edit_description_v2(
         &self,
         target_branch: Option<String>,
@@ -55,27 +196,25 @@ impl GitBranchDescManager {
     ) -> Result<()> {
         // Validate AI summarization usage
         if ai_summarize {
-            match &input_source {
-                InputSource::CommandLine(Some(_)) => {
-                    anyhow::bail!("AI summarization cannot be used with direct text input. Use --input=clipboard, --input=stdin, --input=editor, or --input=issue instead.");
-                }
-                InputSource::CommandLine(None) | InputSource::Clipboard | InputSource::Stdin | InputSource::Issue(_) | InputSource::Editor => {
-                    // Valid combinations
-                }
-            }
+            input_source.validate_for_ai_summarize()?;
         }
+        // Amending replaces a commit, so it commits the current branch as well
+        let commit = commit || self.amend;
 
         // Determine the target branch
         let target_branch = target_branch
             .map(Ok)
             .unwrap_or_else(|| self.get_current_branch())?;
+        // "origin/<branch>" as shown by list means the remote branch
+        let target_branch = self.branch_name_from_listing(&target_branch)?;
 
         // Validate that the branch exists
         self.validate_branch_exists(&target_branch)?;
+        self.require_push_for_remote_only(&target_branch, push)?;
 
         // Determine if we're working on the current branch
         let current_branch = self.get_current_branch()?;
-        let is_current_branch = target_branch == current_branch;
+        let is_current_branch = self.is_working_copy_branch(&target_branch)?;
 
         // Get existing description to determine if this is an add or modify operation
         let existing_description = if is_current_branch {
@@ -87,55 +226,50 @@ impl GitBranchDescManager {
 
         let is_modify = !existing_description.trim().is_empty();
 
-        // Safety check for non-current branches
-        if !is_current_branch && !force {
-            println!(
-                "⚠️  You are about to modify branch '{}' (not current branch '{}')",
-                target_branch, current_branch
-            );
-            print!("Continue? (y/N): ");
-            io::stdout().flush()?;
-
-            let mut input = String::new();
-            io::stdin().read_line(&mut input)?;
+        // Metadata such as the linked issue is kept across edits
+        let existing_file = existing_description;
+        let (existing_description, mut metadata) = split_description_metadata(&existing_file);
 
-            if !input.trim().to_lowercase().starts_with('y') {
-                println!("Operation cancelled.");
-                return Ok(());
-            }
+        // Safety check for non-current branches
+        if target_branch != current_branch
+            && !force
+            && !self.dry_run
+            && !self.confirm_other_branch(
+                &target_branch,
+                &current_branch,
+                &mut io::stdin().lock(),
+            )?
+        {
+            println!("Operation cancelled.");
+            return Ok(());
         }
 
         // Get the description content based on input source
-        let description_content = match input_source {
-            InputSource::CommandLine(Some(desc)) => desc,
-            InputSource::CommandLine(None) => {
-                self.get_interactive_input(&target_branch, &existing_description)?
-            }
-            InputSource::Clipboard => {
-                let mut content = self.get_clipboard_content()?;
-                if ai_summarize {
-                    content = self.ai_summarize_content(&content, ai_timeout)?;
-                }
-                content
-            }
-            InputSource::Stdin => {
-                let mut content = self.get_stdin_content()?;
-                if ai_summarize {
-                    content = self.ai_summarize_content(&content, ai_timeout)?;
-                }
-                content
-            }
-            InputSource::Issue(issue_ref) => {
-                self.get_issue_content(&issue_ref, ai_summarize, ai_timeout)?
-            }
-            InputSource::Editor => {
-                let mut content = self.get_editor_content(&target_branch, &existing_description)?;
-                if ai_summarize {
-                    content = self.ai_summarize_content(&content, ai_timeout)?;
-                }
-                content
-            }
-        };
+        let mut issues = Vec::new();
+        let description_content = self.get_input_content(
+            input_source,
+            &target_branch,
+            &existing_description,
+            ai_summarize,
+            ai_timeout,
+            &mut issues,
+        )?;
+        if !issues.is_empty() {
+            metadata.insert("issue".to_string(), issues.join(", "));
+        }
+        let description_content = join_description_metadata(&description_content, &metadata);
+
+        if self.dry_run {
+            return self.print_dry_run(
+                &target_branch,
+                &existing_file,
+                &description_content,
+                is_current_branch,
+                is_modify,
+                commit || !is_current_branch,
+                push,
+            );
+        }
 
         // Write the description
         if is_current_branch {
@@ -161,6 +295,218 @@ impl GitBranchDescManager {
         Ok(())
     }
 
+    /// Shows what `edit` would do: the new description, its diff against the current one and
+    /// the commit that would be created.
+    #[allow(clippy::too_many_arguments)]
+    fn print_dry_run(
+        &self,
+        branch_name: &str,
+        existing_description: &str,
+        description: &str,
+        is_current_branch: bool,
+        is_modify: bool,
+        commit: bool,
+        push: bool,
+    ) -> Result<()> {
+        println!("🔍 Dry run: nothing is written, committed or pushed");
+        println!();
+        println!("Description for branch '{branch_name}':");
+        println!("{}", description.trim_end());
+        println!();
+
+        let mut patch = git2::Patch::from_buffers(
+            existing_description.as_bytes(),
+            Some(Path::new("BRANCHREADME.md")),
+            description.as_bytes(),
+            Some(Path::new("BRANCHREADME.md")),
+            None,
+        )?;
+        let diff = patch.to_buf()?;
+        if diff.is_empty() {
+            println!("BRANCHREADME.md would not change");
+        } else {
+            print!("{}", String::from_utf8_lossy(&diff));
+        }
+        println!();
+
+        if !commit {
+            println!("Would write BRANCHREADME.md without committing it (use --commit to commit)");
+            return Ok(());
+        }
+
+        let (tip, remote_only) = if is_current_branch {
+            (self.repo.head()?.peel_to_commit()?, false)
+        } else if let Ok(branch_ref) = self
+            .repo
+            .find_reference(&format!("refs/heads/{branch_name}"))
+        {
+            (branch_ref.peel_to_commit()?, false)
+        } else {
+            let tracking_ref = self
+                .remote_tracking_ref(branch_name)
+                .with_context(|| format!("Branch '{branch_name}' not found"))?;
+            (tracking_ref.peel_to_commit()?, true)
+        };
+        let (parent, is_modify, amended) =
+            self.description_commit_parent(branch_name, tip, is_modify)?;
+        let signature = self
+            .repo
+            .signature()
+            .context("Failed to create signature")?;
+        let message =
+            self.description_commit_message(branch_name, description, is_modify, &signature)?;
+
+        if amended {
+            println!("Would amend the description commit at the tip of '{branch_name}':");
+        } else if remote_only {
+            println!("Would commit on top of 'origin/{branch_name}':");
+        } else {
+            println!("Would commit to branch '{branch_name}':");
+        }
+        println!(
+            "  parent:  {} {}",
+            &parent.id().to_string()[..7],
+            parent.summary().unwrap_or_default()
+        );
+        let mut message_lines = message.lines();
+        println!("  message: {}", message_lines.next().unwrap_or_default());
+        for line in message_lines {
+            println!("           {line}");
+        }
+        println!(
+            "  tree:    {} BRANCHREADME.md",
+            if is_modify { "M" } else { "A" }
+        );
+
+        if push || remote_only {
+            println!("Would push to 'origin/{branch_name}'");
+        }
+        Ok(())
+    }
+
+    fn get_input_content(
+        &self,
+        input_source: InputSource,
+        target_branch: &str,
+        existing_description: &str,
+        ai_summarize: bool,
+        ai_timeout: u64,
+        issues: &mut Vec<String>,
+    ) -> Result<String> {
+        let mut issue_title = None;
+
+        // Direct and interactive text is used as typed, everything else may be summarized
+        let summarizable = !matches!(input_source, InputSource::CommandLine(_));
+        let is_issue = input_source.is_issue();
+
+        let mut content = match input_source {
+            InputSource::CommandLine(Some(desc)) => desc,
+            InputSource::CommandLine(None) => {
+                self.get_interactive_input(target_branch, existing_description)?
+            }
+            InputSource::Clipboard => self.get_clipboard_content()?,
+            InputSource::Stdin => self.get_stdin_content()?,
+            InputSource::Issue(issue_ref) => {
+                let issue_ref = match issue_ref {
+                    Some(issue_ref) => issue_ref,
+                    None => {
+                        let detected = self.detect_issue_reference(target_branch)?.with_context(|| {
+                            format!(
+                                "No issue reference found in branch name '{target_branch}'. Use --issue-ref or set branchdesc.issuePattern."
+                            )
+                        })?;
+                        println!("🔗 Detected issue {detected} from branch name '{target_branch}'");
+                        detected
+                    }
+                };
+
+                if is_jira_key(&issue_ref) {
+                    issues.push(issue_ref.clone());
+                    self.get_jira_issue_content(&issue_ref, false, ai_timeout)?
+                } else {
+                    let issue_number = parse_issue_reference(&issue_ref)?;
+                    issues.push(format!("#{issue_number}"));
+                    self.get_issue_content(&issue_ref, false, ai_timeout)?
+                }
+            }
+            InputSource::GitHub(issue_ref) => {
+                let (repo_slug, issue_number) = parse_github_issue_reference(&issue_ref)?;
+                let issue = format!("{}#{issue_number}", repo_slug.unwrap_or_default());
+                issues.push(issue);
+                self.get_github_issue_content(&issue_ref, false, ai_timeout)?
+            }
+            InputSource::Jira(issue_ref) => {
+                let issue_ref = match issue_ref {
+                    Some(issue_ref) => issue_ref,
+                    None => find_jira_key(target_branch).with_context(|| {
+                        format!(
+                            "No Jira key found in branch name '{target_branch}'. Use --issue-ref=PROJ-123."
+                        )
+                    })?,
+                };
+                let (_, issue_key) = parse_jira_issue_reference(&issue_ref)?;
+                issues.push(issue_key);
+                self.get_jira_issue_content(&issue_ref, false, ai_timeout)?
+            }
+            InputSource::MergeRequest {
+                reference,
+                include_discussions,
+            } => self.get_merge_request_content(
+                reference.as_deref(),
+                target_branch,
+                include_discussions,
+                false,
+                ai_timeout,
+            )?,
+            InputSource::Commits => self.get_branch_commits_content(target_branch)?,
+            InputSource::Diff => self.get_branch_diff_content(target_branch)?,
+            InputSource::Combined(sources) => {
+                // Each source becomes a labelled section; AI summarization runs once on the result
+                let mut sections = Vec::new();
+                for source in sources {
+                    let issue_count = issues.len();
+                    let mut label = source.label();
+                    let source_is_issue = source.is_issue();
+                    let content = self.get_input_content(
+                        source,
+                        target_branch,
+                        existing_description,
+                        false,
+                        ai_timeout,
+                        issues,
+                    )?;
+                    if issues.len() > issue_count {
+                        label = format!("{label} {}", issues[issue_count..].join(", "));
+                    }
+                    if source_is_issue && issue_title.is_none() {
+                        issue_title = content.lines().next().map(str::to_string);
+                    }
+                    sections.push(format!("## {label}\n\n{}", content.trim()));
+                }
+
+                sections.join("\n\n")
+            }
+            InputSource::File(path) => self.get_file_content(&path)?,
+            InputSource::Editor => self.get_editor_content(target_branch, existing_description)?,
+        };
+
+        // Issue content starts with the issue title
+        if is_issue {
+            issue_title = content.lines().next().map(str::to_string);
+        }
+
+        if ai_summarize && summarizable {
+            let vars = PromptVars {
+                branch: target_branch.to_string(),
+                issue_title,
+            };
+            content = self.ai_summarize_for_description(&content, &vars, ai_timeout)?;
+        }
+
+        Ok(content)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn edit_description(
         &self,
         target_branch: Option<String>,
@@ -182,7 +528,7 @@ impl GitBranchDescManager {
         } else if stdin {
             InputSource::Stdin
         } else if let Some(issue_ref) = issue {
-            InputSource::Issue(issue_ref)
+            InputSource::Issue(Some(issue_ref))
         } else {
             InputSource::CommandLine(None)
         };
@@ -199,46 +545,167 @@ impl GitBranchDescManager {
         )
     }
 
+    /// Sets the descriptions of many branches at once. Every branch gets its own description
+    /// commit; with `push` all of them go to origin in one atomic push. Returns and prints
+    /// the outcome per branch.
+    pub fn bulk_edit(
+        &self,
+        entries: Vec<BulkEntry>,
+        push: bool,
+    ) -> Result<Vec<(String, BulkOutcome)>> {
+        let current_branch = self.get_current_branch()?;
+        let mut outcomes = Vec::new();
+        let mut updates = Vec::new();
+
+        for entry in entries {
+            let branch = self.branch_name_from_listing(&entry.branch)?;
+            match self.bulk_commit(&branch, &entry.description, &current_branch, push) {
+                Ok(Some(update)) => {
+                    updates.push(update);
+                    outcomes.push((branch, BulkOutcome::Committed));
+                }
+                Ok(None) => outcomes.push((branch, BulkOutcome::Skipped)),
+                Err(err) => outcomes.push((branch, BulkOutcome::Failed(format!("{err:#}")))),
+            }
+        }
 
+        if push && !updates.is_empty() {
+            let pushed = push::push_atomic(&self.repo, "origin", &updates);
+            for update in &updates {
+                if update.local_ref.starts_with(BULK_PUSH_REF_PREFIX)
+                    && let Ok(mut reference) = self.repo.find_reference(&update.local_ref)
+                {
+                    reference.delete()?;
+                }
+            }
 
-
-    pub fn list_descriptions(&self, detailed: bool, all: bool) -> Result<()> {
-        let mut descriptions = Vec::new();
-        let local_branches = self.get_local_branch_list()?;
-        let mut processed_branches = HashSet::new();
-
-        // First, process remote branches
-        let remotes = self.repo.remotes()?;
-        for remote_name in remotes.iter() {
-            if let Some(remote_name) = remote_name {
-                let remote_branches = self.repo.branches(Some(git2::BranchType::Remote))?;
-                for branch in remote_branches {
-                    let (branch, _) = branch.context("Failed to get branch")?;
-                    if let Some(name) = branch.name().context("Failed to get branch name")? {
-                        if name.starts_with(&format!("{remote_name}/")) {
-                            let branch_name = name
-                                .strip_prefix(&format!("{remote_name}/"))
-                                .unwrap_or(name);
-
-                            if let Some(desc) = self.process_branch_description(name, true, all) {
-                                descriptions.push(desc);
-                                processed_branches.insert(branch_name.to_string());
-                            }
+            // A push that failed as a whole is reported for every branch it would have updated
+            let pushed: Vec<std::result::Result<(), String>> = match pushed {
+                Ok(results) => results
+                    .into_iter()
+                    .map(|(_, result)| result.map_err(|err| err.to_string()))
+                    .collect(),
+                Err(err) => updates.iter().map(|_| Err(format!("{err:#}"))).collect(),
+            };
+            for (update, result) in updates.iter().zip(pushed) {
+                if let Some((_, outcome)) =
+                    outcomes.iter_mut().find(|(name, _)| *name == update.branch)
+                {
+                    *outcome = match result {
+                        Ok(()) => BulkOutcome::Pushed,
+                        Err(message) if update.local_ref.starts_with(BULK_PUSH_REF_PREFIX) => {
+                            BulkOutcome::Failed(message)
                         }
-                    }
+                        Err(message) => BulkOutcome::Failed(format!(
+                            "{message} (the description commit remains on the local branch)"
+                        )),
+                    };
                 }
             }
         }
 
-        // Then, process local branches (skip if already processed as remote)
-        for branch_name in local_branches {
-            if !processed_branches.contains(&branch_name) {
-                if let Some(desc) = self.process_branch_description(&branch_name, false, all) {
-                    descriptions.push(desc);
-                }
+        let mut tw = TabWriter::new(io::stdout());
+        writeln!(tw, "BRANCH\tRESULT")?;
+        writeln!(tw, "------\t------")?;
+        for (branch, outcome) in &outcomes {
+            writeln!(tw, "{branch}\t{outcome}")?;
+        }
+        tw.flush()?;
+
+        Ok(outcomes)
+    }
+
+    /// Commits one bulk entry without pushing it. Returns the reference to push, or `None`
+    /// when the description is empty or unchanged.
+    fn bulk_commit(
+        &self,
+        branch_name: &str,
+        description: &str,
+        current_branch: &str,
+        push: bool,
+    ) -> Result<Option<push::RefUpdate>> {
+        if self.validate_branch_exists(branch_name).is_err() {
+            anyhow::bail!("branch not found");
+        }
+        let is_current_branch = branch_name == current_branch && self.repo.workdir().is_some();
+
+        let existing_description = if is_current_branch {
+            self.read_current_branch_description()?
+        } else {
+            self.read_branch_description_from_git(branch_name)?
+                .unwrap_or_default()
+        };
+        let is_modify = !existing_description.trim().is_empty();
+        let (existing_body, metadata) = split_description_metadata(&existing_description);
+        if description.trim().is_empty() || description.trim() == existing_body.trim() {
+            return Ok(None);
+        }
+        let description = join_description_metadata(description, &metadata);
+
+        let local_ref = format!("refs/heads/{branch_name}");
+        if is_current_branch {
+            self.write_current_branch_description(&description)?;
+            self.commit_current_branch_changes(branch_name, is_modify, false)?;
+        } else if self.repo.find_reference(&local_ref).is_ok() {
+            self.commit_to_branch(branch_name, &description, is_modify, false)?;
+        } else {
+            // Remote-only branches have no local branch to keep the commit until the push
+            if !push {
+                anyhow::bail!("branch only exists on origin, use --push to update it");
             }
+            let remote_tip = self
+                .remote_tracking_ref(branch_name)
+                .with_context(|| format!("Branch '{branch_name}' not found"))?
+                .peel_to_commit()?;
+            let (commit, _, _) =
+                self.create_description_commit(branch_name, remote_tip, &description, is_modify)?;
+            let temp_ref = format!("{BULK_PUSH_REF_PREFIX}{branch_name}");
+            self.repo
+                .reference(&temp_ref, commit, true, "branchdesc: bulk")?;
+            return Ok(Some(push::RefUpdate {
+                local_ref: temp_ref,
+                branch: branch_name.to_string(),
+                commit,
+            }));
+        }
+
+        let commit = self.repo.refname_to_id(&local_ref)?;
+        Ok(Some(push::RefUpdate {
+            local_ref,
+            branch: branch_name.to_string(),
+            commit,
+        }))
+    }
+
+    /// Opens all local branches and their descriptions in the editor for `bulk`.
+    pub fn bulk_editor_entries(&self) -> Result<Vec<BulkEntry>> {
+        let mut entries = Vec::new();
+        for branch_name in self.get_local_branch_list()? {
+            let description = self
+                .read_branch_description_from_git(&branch_name)?
+                .unwrap_or_default();
+            let (description, _) = split_description_metadata(&description);
+            entries.push(BulkEntry::new(&branch_name, &description));
         }
 
+        let mut help =
+            String::from("# Each '==> branch <==' line starts the description of that branch.\n");
+        help.push_str(
+            "# Edit the descriptions above the line. Branches left unchanged or empty are\n",
+        );
+        help.push_str("# skipped, sections of other existing branches can be added.\n");
+
+        let buffer = self.edit_in_editor(
+            "git-branch-desc-bulk.md",
+            &bulk::format_editor_buffer(&entries),
+            &help,
+        )?;
+        Ok(bulk::parse_editor_buffer(&buffer))
+    }
+
+    pub fn list_descriptions(&self, detailed: bool, all: bool) -> Result<()> {
+        let descriptions = self.collect_descriptions(all)?;
+
         if descriptions.is_empty() {
             if all {
                 println!("No branches found.");
@@ -260,32 +727,198 @@ impl GitBranchDescManager {
         Ok(())
     }
 
+    /// Collects the descriptions shown by `list`: pushed branches under their remote name
+    /// ("origin/feature"), local-only branches under their local name.
+    pub fn collect_descriptions(&self, all: bool) -> Result<Vec<BranchDescription>> {
+        let mut descriptions = Vec::new();
+        let local_branches = self.get_local_branch_list()?;
+        let mut processed_branches = HashSet::new();
+
+        // First, process remote branches
+        let remotes = self.repo.remotes()?;
+        for remote_name in remotes.iter().flatten() {
+            let remote_branches = self.repo.branches(Some(git2::BranchType::Remote))?;
+            for branch in remote_branches {
+                let (branch, _) = branch.context("Failed to get branch")?;
+                if let Some(name) = branch.name().context("Failed to get branch name")?
+                    && name.starts_with(&format!("{remote_name}/"))
+                {
+                    let branch_name = name
+                        .strip_prefix(&format!("{remote_name}/"))
+                        .unwrap_or(name);
+
+                    if let Some(desc) = self.process_branch_description(name, true, all) {
+                        descriptions.push(desc);
+                        processed_branches.insert(branch_name.to_string());
+                    }
+                }
+            }
+        }
+
+        // Then, process local branches (skip if already processed as remote)
+        for branch_name in local_branches {
+            if !processed_branches.contains(&branch_name)
+                && let Some(desc) = self.process_branch_description(&branch_name, false, all)
+            {
+                descriptions.push(desc);
+            }
+        }
+
+        Ok(descriptions)
+    }
+
+    /// Maps a name from `collect_descriptions` back to the branch name used by edit and
+    /// checkout, e.g. "origin/feature" to "feature".
+    pub fn branch_name_from_listing(&self, listed_name: &str) -> Result<String> {
+        if self
+            .repo
+            .find_reference(&format!("refs/heads/{listed_name}"))
+            .is_ok()
+        {
+            return Ok(listed_name.to_string());
+        }
+
+        for remote_name in self.repo.remotes()?.iter().flatten() {
+            if let Some(branch_name) = listed_name.strip_prefix(&format!("{remote_name}/")) {
+                return Ok(branch_name.to_string());
+            }
+        }
+
+        Ok(listed_name.to_string())
+    }
+
+    /// Checks out a branch like `git checkout`, creating a tracking branch when the branch
+    /// only exists on origin. Fails instead of overwriting local changes.
+    pub fn checkout_branch(&self, branch_name: &str) -> Result<()> {
+        let local_ref = format!("refs/heads/{branch_name}");
+        if self.repo.find_reference(&local_ref).is_err() {
+            let upstream = format!("origin/{branch_name}");
+            let remote_branch = self
+                .repo
+                .find_branch(&upstream, git2::BranchType::Remote)
+                .with_context(|| format!("Branch '{branch_name}' not found"))?;
+            let commit = remote_branch.get().peel_to_commit()?;
+            let mut branch = self.repo.branch(branch_name, &commit, false)?;
+            branch.set_upstream(Some(&upstream))?;
+        }
+
+        let target = self.repo.revparse_single(&local_ref)?;
+        self.repo
+            .checkout_tree(&target, Some(git2::build::CheckoutBuilder::new().safe()))
+            .with_context(|| {
+                format!("Failed to check out '{branch_name}'. Commit or stash your changes first.")
+            })?;
+        self.repo.set_head(&local_ref)?;
+
+        println!("✅ Switched to branch '{branch_name}'");
+        Ok(())
+    }
+
     pub fn get_current_branch(&self) -> Result<String> {
         let head = self.repo.head().context("Failed to get HEAD reference")?;
         let branch_name = head.shorthand().context("Failed to get branch name")?;
         Ok(branch_name.to_string())
     }
 
+    /// Whether the branch is checked out in this working tree, so that its description is the
+    /// BRANCHREADME.md file there. Bare repositories have no working copy.
+    fn is_working_copy_branch(&self, branch_name: &str) -> Result<bool> {
+        Ok(self.repo.workdir().is_some() && branch_name == self.get_current_branch()?)
+    }
+
+    /// BRANCHREADME.md at the root of this working tree, wherever the command was started.
+    fn working_copy_description_path(&self) -> Result<PathBuf> {
+        let workdir = self.repo.workdir().context(
+            "Bare repository has no working tree; descriptions are committed to the branch directly",
+        )?;
+        Ok(workdir.join("BRANCHREADME.md"))
+    }
+
+    /// Reads BRANCHREADME.md from the working tree; in a bare repository the committed
+    /// description of HEAD's branch.
     pub fn read_current_branch_description(&self) -> Result<String> {
-        match fs::read_to_string("BRANCHREADME.md") {
+        if self.repo.is_bare() {
+            return Ok(self
+                .read_branch_description_from_git(&self.get_current_branch()?)?
+                .unwrap_or_default());
+        }
+        match fs::read_to_string(self.working_copy_description_path()?) {
             Ok(content) => Ok(content),
             Err(_) => Ok(String::new()),
         }
     }
 
     pub fn write_current_branch_description(&self, description: &str) -> Result<()> {
-        fs::write("BRANCHREADME.md", description)
+        fs::write(self.working_copy_description_path()?, description)
             .context("Failed to write BRANCHREADME.md file")?;
         Ok(())
     }
 
+    /// Another working tree of this repository that has `branch_name` checked out. Committing
+    /// there from here would leave its index and files behind the branch.
+    fn other_worktree_with_branch(&self, branch_name: &str) -> Result<Option<PathBuf>> {
+        let mut repos = Vec::new();
+        if self.repo.is_worktree() {
+            // The main working tree is not among the linked ones
+            repos.push(Repository::open(common_dir(&self.repo))?);
+        }
+        for name in self.repo.worktrees()?.iter().flatten() {
+            let worktree = self.repo.find_worktree(name)?;
+            if worktree.validate().is_ok() {
+                repos.push(Repository::open_from_worktree(&worktree)?);
+            }
+        }
+
+        let own_git_dir = fs::canonicalize(self.repo.path())?;
+        for repo in repos {
+            let (Some(workdir), Ok(head)) = (repo.workdir(), repo.head()) else {
+                continue;
+            };
+            if head.is_branch()
+                && head.shorthand() == Some(branch_name)
+                && fs::canonicalize(repo.path())? != own_git_dir
+            {
+                return Ok(Some(workdir.to_path_buf()));
+            }
+        }
+        Ok(None)
+    }
+
     pub fn commit_current_branch_changes(
         &self,
         branch_name: &str,
         is_modify: bool,
         push: bool,
     ) -> Result<()> {
-        // Stage the BRANCHREADME.md file
+        if self.repo.is_bare() {
+            anyhow::bail!(
+                "Bare repository has no working tree to commit from; edit the branch description directly"
+            );
+        }
+
+        // Only the description goes into the commit, other staged changes stay staged
+        let head_commit = self.repo.head()?.peel_to_commit()?;
+        let other_staged = self.staged_paths_besides_description(&head_commit)?;
+        if !other_staged.is_empty() {
+            println!(
+                "⚠️  {} other staged file(s) are left out of the description commit and stay staged: {}",
+                other_staged.len(),
+                other_staged.join(", ")
+            );
+        }
+
+        let description = fs::read_to_string(self.working_copy_description_path()?)
+            .context("Failed to read BRANCHREADME.md")?;
+        let (commit_id, commit_message, amended) =
+            self.create_description_commit(branch_name, head_commit, &description, is_modify)?;
+        let reflog_action = if amended { "commit (amend)" } else { "commit" };
+        self.repo
+            .head()?
+            .resolve()?
+            .set_target(commit_id, &format!("{reflog_action}: {commit_message}"))
+            .context("Failed to update the current branch")?;
+
+        // Stage the BRANCHREADME.md file so the index matches the new commit
         let mut index = self
             .repo
             .index()
@@ -295,41 +928,29 @@ impl GitBranchDescManager {
             .context("Failed to stage BRANCHREADME.md")?;
         index.write().context("Failed to write index")?;
 
-        // Create commit
-        let tree_id = index.write_tree().context("Failed to write tree")?;
-        let tree = self
-            .repo
-            .find_tree(tree_id)
-            .context("Failed to find tree")?;
-        let parent_commit = self.repo.head()?.peel_to_commit()?;
-        let signature = self
-            .repo
-            .signature()
-            .context("Failed to create signature")?;
-
-        let action = if is_modify { "Update" } else { "Add" };
-        let commit_message = format!("{action} branch description [skip ci]");
-
-        self.repo
-            .commit(
-                Some("HEAD"),
-                &signature,
-                &signature,
-                &commit_message,
-                &tree,
-                &[&parent_commit],
-            )
-            .context("Failed to create commit")?;
-
-        println!("✅ Committed changes to branch '{branch_name}'");
+        print_commit_result(branch_name, amended);
 
         if push {
-            self.push_current_branch(branch_name)?;
+            self.push_branch(branch_name)?;
         }
 
         Ok(())
     }
 
+    /// Files whose staged content differs from `head`, except BRANCHREADME.md.
+    fn staged_paths_besides_description(&self, head: &git2::Commit) -> Result<Vec<String>> {
+        let diff = self
+            .repo
+            .diff_tree_to_index(Some(&head.tree()?), None, None)
+            .context("Failed to compare the index with HEAD")?;
+        Ok(diff
+            .deltas()
+            .filter_map(|delta| delta.new_file().path().or(delta.old_file().path()))
+            .filter(|path| *path != Path::new("BRANCHREADME.md"))
+            .map(|path| path.display().to_string())
+            .collect())
+    }
+
     pub fn commit_to_branch(
         &self,
         branch_name: &str,
@@ -339,16 +960,108 @@ impl GitBranchDescManager {
     ) -> Result<()> {
         // Get the target branch reference
         let branch_ref_name = format!("refs/heads/{branch_name}");
-        let branch_ref = self
-            .repo
-            .find_reference(&branch_ref_name)
-            .with_context(|| format!("Branch '{branch_name}' not found"))?;
+        let branch_ref = match self.repo.find_reference(&branch_ref_name) {
+            Ok(branch_ref) => branch_ref,
+            Err(_) if self.remote_tracking_ref(branch_name).is_some() => {
+                self.require_push_for_remote_only(branch_name, push)?;
+                return self.commit_to_remote_branch(branch_name, description, is_modify);
+            }
+            Err(err) => {
+                return Err(err).with_context(|| format!("Branch '{branch_name}' not found"));
+            }
+        };
+
+        if let Some(worktree) = self.other_worktree_with_branch(branch_name)? {
+            anyhow::bail!(
+                "Branch '{branch_name}' is checked out in the worktree at {}. Edit its description there.",
+                worktree.display()
+            );
+        }
 
         let branch_commit = branch_ref.peel_to_commit()?;
-        let branch_tree = branch_commit.tree()?;
+        let (new_commit_id, commit_message, amended) =
+            self.create_description_commit(branch_name, branch_commit, description, is_modify)?;
+
+        // Update the branch reference to point to the new commit
+        let mut branch_ref = self.repo.find_reference(&branch_ref_name)?;
+        branch_ref.set_target(new_commit_id, &commit_message)?;
+
+        print_commit_result(branch_name, amended);
+
+        if push {
+            self.push_branch(branch_name)?;
+        }
+
+        Ok(())
+    }
+
+    /// Commits on top of `origin/<branch>` and pushes straight to the remote branch, so a
+    /// branch that only exists on the remote is annotated without creating a local branch.
+    fn commit_to_remote_branch(
+        &self,
+        branch_name: &str,
+        description: &str,
+        is_modify: bool,
+    ) -> Result<()> {
+        let tracking_ref = self
+            .remote_tracking_ref(branch_name)
+            .with_context(|| format!("Branch '{branch_name}' not found"))?;
+        let remote_tip = tracking_ref.peel_to_commit()?;
+        let (new_commit_id, _, _) =
+            self.create_description_commit(branch_name, remote_tip, description, is_modify)?;
+
+        push::push_commit(
+            &self.repo,
+            "origin",
+            new_commit_id,
+            branch_name,
+            self.force_with_lease,
+        )?;
+        println!("✅ Committed and pushed the description of remote branch 'origin/{branch_name}'");
+        Ok(())
+    }
+
+    /// Whether `branch_name` only exists as `origin/<branch>`, without a local branch.
+    pub fn is_remote_only_branch(&self, branch_name: &str) -> bool {
+        self.repo
+            .find_reference(&format!("refs/heads/{branch_name}"))
+            .is_err()
+            && self.remote_tracking_ref(branch_name).is_some()
+    }
+
+    /// The description commit of a remote-only branch goes straight to the shared remote
+    /// branch, so that has to be asked for with `push`.
+    fn require_push_for_remote_only(&self, branch_name: &str, push: bool) -> Result<()> {
+        if !push && self.is_remote_only_branch(branch_name) {
+            anyhow::bail!(
+                "Branch '{branch_name}' only exists on origin, so its description commit is pushed to origin/{branch_name} right away. Use --push to do that."
+            );
+        }
+        Ok(())
+    }
+
+    fn remote_tracking_ref(&self, branch_name: &str) -> Option<git2::Reference<'_>> {
+        self.repo
+            .find_reference(&format!("refs/remotes/origin/{branch_name}"))
+            .ok()
+    }
+
+    /// Creates (without updating any reference) a commit that sets BRANCHREADME.md on top of
+    /// `tip`, or replaces `tip` with `--amend`. Returns the commit, its message and whether
+    /// it amends.
+    fn create_description_commit(
+        &self,
+        branch_name: &str,
+        tip: git2::Commit,
+        description: &str,
+        is_modify: bool,
+    ) -> Result<(git2::Oid, String, bool)> {
+        let tip_tree = tip.tree()?;
+        let (parent_commit, is_modify, amended) =
+            self.description_commit_parent(branch_name, tip, is_modify)?;
 
         // Create a new tree with the BRANCHREADME.md file
-        let mut tree_builder = self.repo.treebuilder(Some(&branch_tree))?;
+        let mut tree_builder = self.repo.treebuilder(Some(&tip_tree))?;
 
         // Create blob for the description content
         let blob_id = self.repo.blob(description.as_bytes())?;
@@ -363,29 +1076,128 @@ impl GitBranchDescManager {
             .repo
             .signature()
             .context("Failed to create signature")?;
-        let action = if is_modify { "Update" } else { "Add" };
-        let commit_message = format!("{action} branch description [skip ci]");
+        let commit_message =
+            self.description_commit_message(branch_name, description, is_modify, &signature)?;
 
-        let new_commit_id = self.repo.commit(
-            None, // Don't update any reference yet
-            &signature,
+        let new_commit_id = signing::create_commit(
+            &self.repo,
             &signature,
             &commit_message,
             &new_tree,
-            &[&branch_commit],
+            &[&parent_commit],
         )?;
+        Ok((new_commit_id, commit_message, amended))
+    }
 
-        // Update the branch reference to point to the new commit
-        let mut branch_ref = self.repo.find_reference(&branch_ref_name)?;
-        branch_ref.set_target(new_commit_id, &commit_message)?;
+    /// Picks the parent of the new description commit: the tip itself, or with `--amend` the
+    /// tip's parent when the tip can be replaced. Returns the parent, whether the commit
+    /// updates an existing description, and whether the tip is amended.
+    fn description_commit_parent<'repo>(
+        &'repo self,
+        branch_name: &str,
+        tip: git2::Commit<'repo>,
+        is_modify: bool,
+    ) -> Result<(git2::Commit<'repo>, bool, bool)> {
+        if !self.amend {
+            return Ok((tip, is_modify, false));
+        }
+        if !self.is_amendable_description_commit(&tip)? {
+            println!(
+                "💡 The tip of '{branch_name}' is not an unpushed description commit, adding a new commit"
+            );
+            return Ok((tip, is_modify, false));
+        }
 
-        println!("✅ Committed changes to branch '{branch_name}'");
+        // The replaced commit may have added the description
+        let parent = tip.parent(0)?;
+        let is_modify = parent.tree()?.get_name("BRANCHREADME.md").is_some();
+        Ok((parent, is_modify, true))
+    }
 
-        if push {
-            self.push_branch(branch_name)?;
+    /// Whether `commit` only touches BRANCHREADME.md and is on no remote-tracking branch,
+    /// so replacing it does not rewrite published history.
+    fn is_amendable_description_commit(&self, commit: &git2::Commit) -> Result<bool> {
+        if !self.is_description_only_commit(commit)? {
+            return Ok(false);
         }
 
-        Ok(())
+        for reference in self.repo.references_glob("refs/remotes/*")? {
+            let Some(remote_tip) = reference?.target() else {
+                continue;
+            };
+            if remote_tip == commit.id()
+                || self.repo.graph_descendant_of(remote_tip, commit.id())?
+            {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Message settings from env vars, `git config` (`branchdesc.commit.*`) and config files.
+    pub fn commit_message_settings(&self) -> Result<MessageSettings> {
+        let file = FileConfig::load(self.repo.workdir())?.commit;
+        let defaults = MessageSettings::default();
+
+        let trailers: Vec<String> = match self
+            .repo
+            .config()?
+            .multivar("branchdesc.commit.trailer", None)
+        {
+            Ok(mut entries) => {
+                let mut trailers = Vec::new();
+                while let Some(entry) = entries.next() {
+                    if let Some(value) = entry?.value() {
+                        trailers.push(value.to_string());
+                    }
+                }
+                trailers
+            }
+            Err(_) => Vec::new(),
+        };
+
+        Ok(MessageSettings {
+            template: self
+                .get_config_value("BRANCHDESC_COMMIT_TEMPLATE", "branchdesc.commit.template")
+                .or(file.template)
+                .unwrap_or(defaults.template),
+            skip_ci: self
+                .get_config_bool("BRANCHDESC_COMMIT_SKIP_CI", "branchdesc.commit.skipCi")?
+                .or(file.skip_ci)
+                .unwrap_or(defaults.skip_ci),
+            signoff: self
+                .get_config_bool("BRANCHDESC_COMMIT_SIGNOFF", "branchdesc.commit.signoff")?
+                .or(file.signoff)
+                .unwrap_or(defaults.signoff),
+            trailers: if trailers.is_empty() {
+                file.trailers.unwrap_or(defaults.trailers)
+            } else {
+                trailers
+            },
+        })
+    }
+
+    fn description_commit_message(
+        &self,
+        branch_name: &str,
+        description: &str,
+        is_modify: bool,
+        committer: &git2::Signature,
+    ) -> Result<String> {
+        let settings = self.commit_message_settings()?;
+        let (body, metadata) = split_description_metadata(description);
+        let vars = MessageVars::new(
+            is_modify,
+            branch_name,
+            &body,
+            metadata.get("issue").cloned(),
+        );
+        let committer = format!(
+            "{} <{}>",
+            committer.name().unwrap_or_default(),
+            committer.email().unwrap_or_default()
+        );
+        Ok(message::render(&settings, &vars, &committer))
     }
 
     pub fn validate_branch_exists(&self, branch_name: &str) -> Result<()> {
@@ -429,6 +1241,169 @@ impl GitBranchDescManager {
         Ok(content.trim().to_string())
     }
 
+    pub fn set_clipboard_content(&self, text: &str) -> Result<()> {
+        let mut clipboard = Clipboard::new().context("Failed to access clipboard")?;
+        clipboard
+            .set_text(text.to_string())
+            .context("Failed to write to clipboard")?;
+        Ok(())
+    }
+
+    /// Commits the staged changes with `message`, letting the user review it in the editor
+    /// like `git commit --edit`.
+    pub fn commit_staged_with_message(&self, message: &str) -> Result<()> {
+        let message_file = self.repo.path().join("BRANCHDESC_COMMIT_MSG");
+        fs::write(&message_file, message).context("Failed to write commit message file")?;
+
+        let status = Command::new("git")
+            .arg("commit")
+            .arg("--edit")
+            .arg("-F")
+            .arg(&message_file)
+            .status()
+            .context("Failed to execute git commit command");
+        let _ = fs::remove_file(&message_file);
+
+        if !status?.success() {
+            anyhow::bail!("git commit failed");
+        }
+        Ok(())
+    }
+
+    pub fn get_file_content(&self, path: &Path) -> Result<String> {
+        let bytes =
+            fs::read(path).with_context(|| format!("Failed to read file '{}'", path.display()))?;
+        Ok(decode_text(&bytes).trim().to_string())
+    }
+
+    /// Re-commits the description whenever the file changes, until the process is interrupted.
+    pub fn watch_description_file(
+        &self,
+        target_branch: Option<String>,
+        path: &Path,
+        push: bool,
+        force: bool,
+    ) -> Result<()> {
+        self.watch_description_file_until(
+            target_branch,
+            path,
+            push,
+            force,
+            WATCH_POLL_INTERVAL,
+            &mut io::stdin().lock(),
+            || true,
+        )
+    }
+
+    /// `watch_description_file` that reads the confirmation for a non-current branch from
+    /// `input`, checks the file every `poll_interval` and stops once `keep_watching` returns
+    /// false.
+    #[allow(clippy::too_many_arguments)]
+    pub fn watch_description_file_until(
+        &self,
+        target_branch: Option<String>,
+        path: &Path,
+        push: bool,
+        force: bool,
+        poll_interval: std::time::Duration,
+        input: &mut impl io::BufRead,
+        mut keep_watching: impl FnMut() -> bool,
+    ) -> Result<()> {
+        let target_branch = target_branch
+            .map(Ok)
+            .unwrap_or_else(|| self.get_current_branch())?;
+        self.validate_branch_exists(&target_branch)?;
+        self.require_push_for_remote_only(&target_branch, push)?;
+
+        // Ask once before watching, so that later saves commit without asking again
+        let current_branch = self.get_current_branch()?;
+        if target_branch != current_branch
+            && !force
+            && !self.dry_run
+            && !self.confirm_other_branch(&target_branch, &current_branch, input)?
+        {
+            println!("Operation cancelled.");
+            return Ok(());
+        }
+
+        println!(
+            "👀 Watching '{}' for branch '{target_branch}' (Ctrl-C to stop)...",
+            path.display()
+        );
+
+        let mut last_modified = None;
+        loop {
+            let modified = fs::metadata(path).and_then(|m| m.modified()).ok();
+            if modified.is_some() && modified != last_modified {
+                last_modified = modified;
+
+                // Editors that save by truncating or renaming make reads fail briefly,
+                // the finished save is another change
+                let content = match self.get_file_content(path) {
+                    Ok(content) => content,
+                    Err(e) => {
+                        eprintln!("⚠️  {e:#}, waiting for the next change");
+                        continue;
+                    }
+                };
+
+                // Skip saves that do not change the stored description
+                let existing = if self.is_working_copy_branch(&target_branch)? {
+                    self.read_current_branch_description()?
+                } else {
+                    self.read_branch_description_from_git(&target_branch)?
+                        .unwrap_or_default()
+                };
+                let (existing, _) = split_description_metadata(&existing);
+
+                if !content.is_empty()
+                    && content != existing.trim()
+                    && let Err(e) = self.edit_description_v2(
+                        Some(target_branch.clone()),
+                        InputSource::File(path.to_path_buf()),
+                        false,
+                        0,
+                        true,
+                        push,
+                        true,
+                    )
+                {
+                    eprintln!("❌ {e:#}");
+                }
+            }
+
+            std::thread::sleep(poll_interval);
+            if !keep_watching() {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Asks whether to modify a branch other than the current one, reading the answer from
+    /// `input`.
+    fn confirm_other_branch(
+        &self,
+        target_branch: &str,
+        current_branch: &str,
+        input: &mut impl io::BufRead,
+    ) -> Result<bool> {
+        println!(
+            "⚠️  You are about to modify branch '{}' (not current branch '{}')",
+            target_branch, current_branch
+        );
+        if self.is_remote_only_branch(target_branch) {
+            println!(
+                "⚠️  It only exists on origin, the description commit is pushed to origin/{target_branch}"
+            );
+        }
+        print!("Continue? (y/N): ");
+        io::stdout().flush()?;
+
+        let mut answer = String::new();
+        input.read_line(&mut answer)?;
+        Ok(answer.trim().to_lowercase().starts_with('y'))
+    }
+
     pub fn get_stdin_content(&self) -> Result<String> {
         if io::stdin().is_terminal() {
             anyhow::bail!(
@@ -473,19 +1448,7 @@ impl GitBranchDescManager {
         let issue_number = parse_issue_reference(issue_ref)?;
 
         // Use glab to get issue information
-        let output = Command::new("glab")
-            .args(["issue", "view", &issue_number, "--output", "json"])
-            .output()
-            .context(
-                "Failed to execute glab command. Make sure glab is installed and configured.",
-            )?;
-
-        if !output.status.success() {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("glab command failed: {error_msg}");
-        }
-
-        let json_output = String::from_utf8_lossy(&output.stdout);
+        let json_output = run_glab(&["issue", "view", &issue_number, "--output", "json"])?;
         let mut content = parse_issue_json(&json_output)?;
 
         if ai_summarize {
@@ -495,247 +1458,850 @@ impl GitBranchDescManager {
         Ok(content)
     }
 
-    fn get_editor_content(&self, target_branch: &str, existing_description: &str) -> Result<String> {
-        use std::env;
-        use std::fs;
-        use std::process::Command;
-
-        // Create temporary file
-        let temp_dir = env::temp_dir();
-        let temp_file = temp_dir.join(format!("git-branch-desc-{}.txt", target_branch.replace("/", "-")));
+    /// Extracts an issue reference from the branch name using `branchdesc.issuePattern`.
+    /// The first capture group (or the whole match) is the issue number or Jira key.
+    pub fn detect_issue_reference(&self, branch_name: &str) -> Result<Option<String>> {
+        let pattern = self
+            .get_config_value("BRANCHDESC_ISSUE_PATTERN", "branchdesc.issuePattern")
+            .unwrap_or_else(|| DEFAULT_ISSUE_PATTERN.to_string());
+        extract_issue_from_branch(branch_name, &pattern)
+    }
 
-        // Get list output for prefilled content
-        let list_content = self.get_list_content_for_editor()?;
-        
-        // Create prefilled content
-        let mut prefill_content = String::new();
-        if !existing_description.trim().is_empty() {
-            prefill_content.push_str("# Existing description (edit lines starting with # to modify):\n");
-            for line in existing_description.lines() {
-                prefill_content.push_str(&format!("# {}\n", line));
+    pub fn get_merge_request_content(
+        &self,
+        mr_ref: Option<&str>,
+        branch_name: &str,
+        include_discussions: bool,
+        ai_summarize: bool,
+        ai_timeout: u64,
+    ) -> Result<String> {
+        // Use the given MR or look up the open MR whose source branch is the target branch
+        let mr_number = match mr_ref {
+            Some(mr_ref) => parse_merge_request_reference(mr_ref)?,
+            None => {
+                let json_output = run_glab(&[
+                    "mr",
+                    "list",
+                    "--source-branch",
+                    branch_name,
+                    "--output",
+                    "json",
+                ])?;
+                parse_merge_request_list_json(&json_output)?.with_context(|| {
+                    format!("No open merge request found for branch '{branch_name}'")
+                })?
             }
-            prefill_content.push('\n');
-        }
-        
-        prefill_content.push_str("# Enter your branch description below. Lines starting with # will be included.\n");
-        prefill_content.push_str("# Empty file or no # lines = no description added.\n");
-        prefill_content.push_str("#\n");
-        prefill_content.push_str("# Current branch descriptions:\n");
-        for line in list_content.lines() {
-            prefill_content.push_str(&format!("# {}\n", line));
-        }
-        
-        // Write prefilled content to temp file
-        fs::write(&temp_file, &prefill_content)?;
-
-        // Determine editor command
-        let editor = if cfg!(target_os = "windows") {
-            "notepad.exe".to_string()
-        } else {
-            env::var("EDITOR").unwrap_or_else(|_| "nano".to_string())
         };
 
-        // Open editor
-        let status = Command::new(editor)
-            .arg(&temp_file)
-            .status()
-            .context("Failed to open editor")?;
+        let json_output = run_glab(&["mr", "view", &mr_number, "--output", "json"])?;
+        let mut content = parse_issue_json(&json_output)?;
 
-        if !status.success() {
-            anyhow::bail!("Editor exited with non-zero status");
+        if include_discussions {
+            // The API returns 20 discussions per page unless asked for more
+            let discussions_path =
+                format!("projects/:id/merge_requests/{mr_number}/discussions?per_page=100");
+            let json_output = run_glab(&["api", "--paginate", &discussions_path])?;
+            let discussions = parse_merge_request_discussions_json(&json_output)?;
+            if !discussions.is_empty() {
+                content = format!("{content}\n\nDiscussion:\n{discussions}");
+            }
         }
 
-        // Read the edited content
-        let edited_content = fs::read_to_string(&temp_file)
-            .context("Failed to read edited content")?;
+        if ai_summarize {
+            content = self.ai_summarize_content(&content, ai_timeout)?;
+        }
 
-        // Clean up temp file
-        let _ = fs::remove_file(&temp_file);
+        Ok(content)
+    }
 
-        // Process the content - extract all non-template lines
-        let mut description_lines = Vec::new();
-        let mut has_changes = false;
-        let original_lines: HashSet<&str> = prefill_content.lines().collect();
-        
-        for line in edited_content.lines() {
-            let trimmed_line = line.trim();
-            
-            // Skip empty lines
-            if trimmed_line.is_empty() {
-                continue;
-            }
-            
-            // Skip original template lines (unchanged)
-            if original_lines.contains(line) {
+    /// Collects the messages of all commits on the branch since it left its parent branch,
+    /// oldest first. Description-only commits are skipped.
+    pub fn get_branch_commits_content(&self, branch_name: &str) -> Result<String> {
+        let (parent_branch, merge_base) = self.get_branch_merge_base(branch_name)?;
+        let branch_commit = self.find_branch_commit(branch_name)?;
+
+        let mut revwalk = self.repo.revwalk()?;
+        revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::REVERSE)?;
+        revwalk.push(branch_commit.id())?;
+        revwalk.hide(merge_base)?;
+
+        let mut entries = Vec::new();
+        for oid in revwalk {
+            let commit = self.repo.find_commit(oid?)?;
+            if self.is_description_only_commit(&commit)? {
                 continue;
             }
-            
-            // Process both # prefixed and regular lines
-            if line.starts_with('#') {
-                let content = line.trim_start_matches('#').trim();
-                if !content.is_empty() {
-                    description_lines.push(content.to_string());
-                    has_changes = true;
+
+            let message = commit.message().unwrap_or("").trim();
+            let mut lines = message.lines();
+            let summary = lines.next().unwrap_or("");
+            let body: Vec<&str> = lines.skip_while(|line| line.trim().is_empty()).collect();
+
+            let mut entry = format!("- {summary}");
+            for line in body {
+                entry.push('\n');
+                if !line.trim().is_empty() {
+                    entry.push_str("  ");
+                    entry.push_str(line.trim_end());
                 }
-            } else if !trimmed_line.is_empty() {
-                description_lines.push(trimmed_line.to_string());
-                has_changes = true;
             }
+            entries.push(entry);
         }
 
-        if !has_changes {
-            anyhow::bail!("No description changes detected (file was not modified or contains no valid # lines)");
+        if entries.is_empty() {
+            anyhow::bail!("Branch '{branch_name}' has no commits ahead of '{parent_branch}'");
         }
 
-        Ok(description_lines.join("\n"))
+        Ok(entries.join("\n"))
     }
 
-    fn get_list_content_for_editor(&self) -> Result<String> {
-        let mut output = Vec::new();
-        let mut tw = TabWriter::new(&mut output);
-        
-        let mut descriptions = Vec::new();
-        let local_branches = self.get_local_branch_list()?;
-        let mut processed_branches = HashSet::new();
+    /// Produces the unified diff between the merge-base with the parent branch and the branch
+    /// tip, excluding the description file itself.
+    pub fn get_branch_diff_content(&self, branch_name: &str) -> Result<String> {
+        let (parent_branch, merge_base) = self.get_branch_merge_base(branch_name)?;
+        let branch_tree = self.find_branch_commit(branch_name)?.tree()?;
+        let base_tree = self.repo.find_commit(merge_base)?.tree()?;
 
-        // Process remote branches first
-        let remotes = self.repo.remotes()?;
-        for remote_name in remotes.iter() {
-            if let Some(remote_name) = remote_name {
-                let remote_branches = self.repo.branches(Some(git2::BranchType::Remote))?;
-                for branch in remote_branches {
-                    let (branch, _) = branch.context("Failed to get branch")?;
-                    if let Some(name) = branch.name().context("Failed to get branch name")? {
-                        if name.starts_with(&format!("{remote_name}/")) {
-                            let branch_name = name.strip_prefix(&format!("{remote_name}/")).unwrap();
-                            if let Ok(Some(desc)) = self.read_branch_description_from_git(name) {
-                                if !desc.trim().is_empty() {
-                                    let truncated = if desc.len() > 50 {
-                                        format!("{}...", &desc[..47])
-                                    } else {
-                                        desc.clone()
-                                    };
-                                    descriptions.push(BranchDescription {
-                                        branch: format!("{} (remote)", branch_name),
-                                        description: truncated,
-                                    });
-                                    processed_branches.insert(branch_name.to_string());
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
+        let diff = self
+            .repo
+            .diff_tree_to_tree(Some(&base_tree), Some(&branch_tree), None)
+            .context("Failed to compute diff against parent branch")?;
+        let content = format_patch(&diff)?;
 
-        // Process local branches
-        for branch_name in local_branches {
-            if processed_branches.contains(&branch_name) {
-                continue;
-            }
-            if let Ok(Some(desc)) = self.read_branch_description_from_git(&branch_name) {
-                if !desc.trim().is_empty() {
-                    let truncated = if desc.len() > 50 {
-                        format!("{}...", &desc[..47])
-                    } else {
-                        desc.clone()
-                    };
-                    descriptions.push(BranchDescription {
-                        branch: branch_name,
-                        description: truncated,
-                    });
-                }
-            }
+        if content.trim().is_empty() {
+            anyhow::bail!("Branch '{branch_name}' has no changes compared to '{parent_branch}'");
         }
 
-        // Write table headers
-        writeln!(tw, "Branch\tDescription")?;
-        writeln!(tw, "------\t-----------")?;
-
-        // Write descriptions
-        for desc in descriptions {
-            writeln!(tw, "{}\t{}", desc.branch, desc.description)?;
-        }
+        Ok(content)
+    }
 
-        tw.flush()?;
-        Ok(String::from_utf8_lossy(&output).to_string())
+    /// Produces the unified diff of the staged changes, excluding the description file.
+    pub fn get_staged_diff_content(&self) -> Result<String> {
+        let head_tree = self.repo.head()?.peel_to_tree()?;
+        let diff = self
+            .repo
+            .diff_tree_to_index(Some(&head_tree), None, None)
+            .context("Failed to compute diff of staged changes")?;
+        format_patch(&diff)
     }
 
-    pub fn ai_summarize_content(&self, content: &str, timeout_seconds: u64) -> Result<String> {
-        // Validate content length - git diffs can be very large
-        const MAX_CONTENT_LENGTH: usize = 8000; // Reasonable limit for AI processing
-        let content_to_process = if content.len() > MAX_CONTENT_LENGTH {
-            println!(
-                "Content is large ({} chars), truncating to {} chars for AI processing...",
-                content.len(),
-                MAX_CONTENT_LENGTH
-            );
-            &content[..MAX_CONTENT_LENGTH]
-        } else {
-            content
-        };
+    /// Generates a merge request description from the stored branch description, the commit
+    /// list and the diff against the parent branch.
+    pub fn generate_mr_text(
+        &self,
+        target_branch: Option<String>,
+        ai_timeout: u64,
+    ) -> Result<String> {
+        let target_branch = target_branch
+            .map(Ok)
+            .unwrap_or_else(|| self.get_current_branch())?;
+        self.validate_branch_exists(&target_branch)?;
 
-        // Determine if this looks like a git diff
-        let is_git_diff = content_to_process.contains("diff --git")
-            || content_to_process.contains("@@")
-            || content_to_process
-                .lines()
-                .any(|line| line.starts_with("+++") || line.starts_with("---"));
+        let (context, metadata) = self.get_branch_context(&target_branch, false)?;
+        let mut text = self.generate_from_branch_context(
+            "mr-text",
+            ai::DEFAULT_MR_PROMPT,
+            &target_branch,
+            &context,
+            &metadata,
+            ai_timeout,
+        )?;
 
-        let system_prompt = if is_git_diff {
-            "You are an expert software engineer. Create a concise 2-3 sentence branch description from this git diff. Focus on the main changes and their purpose. Do not include implementation details or file names."
-        } else {
-            "You are an expert software engineer. Create a concise 2-3 sentence branch description from this content. Focus on the main goals and requirements. Keep it professional and actionable."
-        };
+        if let Some(issue) = metadata.get("issue") {
+            text.push_str(&format!("\n\nRelated to {issue}"));
+        }
+        Ok(text)
+    }
 
-        let user_prompt = format!("Content to summarize:\n\n{}", content_to_process);
+    /// Generates a conventional commit message for the staged changes, or for the whole
+    /// branch when nothing is staged (e.g. for a squash merge), with the branch context.
+    pub fn generate_commit_message(
+        &self,
+        target_branch: Option<String>,
+        ai_timeout: u64,
+    ) -> Result<String> {
+        let target_branch = target_branch
+            .map(Ok)
+            .unwrap_or_else(|| self.get_current_branch())?;
+        self.validate_branch_exists(&target_branch)?;
+
+        let include_staged = target_branch == self.get_current_branch()?;
+        let (context, metadata) = self.get_branch_context(&target_branch, include_staged)?;
+        let mut message = self.generate_from_branch_context(
+            "commit-msg",
+            ai::DEFAULT_COMMIT_PROMPT,
+            &target_branch,
+            &context,
+            &metadata,
+            ai_timeout,
+        )?;
+
+        if let Some(issue) = metadata.get("issue") {
+            message.push_str(&format!("\n\nRefs: {issue}"));
+        }
+        Ok(message)
+    }
+
+    /// Collects description, commits and changes of a branch as labelled sections. The
+    /// staged changes replace the branch diff when `include_staged` is set and there are any.
+    fn get_branch_context(
+        &self,
+        branch_name: &str,
+        include_staged: bool,
+    ) -> Result<(String, BTreeMap<String, String>)> {
+        let description = if self.is_working_copy_branch(branch_name)? {
+            self.read_current_branch_description()?
+        } else {
+            self.read_branch_description_from_git(branch_name)?
+                .unwrap_or_default()
+        };
+        let (description, metadata) = split_description_metadata(&description);
+
+        // Surface a missing parent branch instead of silently dropping commits and diff
+        self.get_branch_merge_base(branch_name)?;
+
+        let mut sections = Vec::new();
+        if !description.trim().is_empty() {
+            sections.push(format!("## Branch description\n\n{}", description.trim()));
+        }
+        if let Ok(commits) = self.get_branch_commits_content(branch_name) {
+            sections.push(format!("## Commits\n\n{commits}"));
+        }
+
+        let staged = if include_staged {
+            self.get_staged_diff_content()?
+        } else {
+            String::new()
+        };
+        if !staged.trim().is_empty() {
+            sections.push(format!("## Staged changes\n\n{staged}"));
+        } else if let Ok(diff) = self.get_branch_diff_content(branch_name) {
+            sections.push(format!("## Changes\n\n{diff}"));
+        }
+
+        if sections.is_empty() {
+            anyhow::bail!(
+                "Branch '{branch_name}' has no description, commits or changes to describe"
+            );
+        }
+        Ok((sections.join("\n\n"), metadata))
+    }
+
+    fn generate_from_branch_context(
+        &self,
+        prompt_name: &str,
+        builtin_prompt: &str,
+        branch_name: &str,
+        context: &str,
+        metadata: &BTreeMap<String, String>,
+        ai_timeout: u64,
+    ) -> Result<String> {
+        let settings = self.ai_settings()?;
+        let vars = PromptVars {
+            branch: branch_name.to_string(),
+            issue_title: metadata.get("issue").cloned(),
+        };
+        let system_prompt = settings.named_prompt(prompt_name, builtin_prompt, &vars);
+
+        let text = self.generate_with_ai(&settings, &system_prompt, context, ai_timeout, false)?;
+        Ok(strip_code_fence(&text))
+    }
+
+    /// Determines the branch a gitflow branch was started from: the per-branch base recorded
+    /// by git-flow, the master branch for hotfixes, and the develop branch otherwise.
+    pub fn get_parent_branch(&self, branch_name: &str) -> Result<String> {
+        let config = self.repo.config()?;
+        let get = |key: &str| config.get_string(key).ok().filter(|v| !v.trim().is_empty());
+
+        if let Some(base) = get(&format!("gitflow.branch.{branch_name}.base")) {
+            return Ok(base);
+        }
+
+        let hotfix_prefix = get("gitflow.prefix.hotfix").unwrap_or_else(|| "hotfix/".to_string());
+        if branch_name.starts_with(&hotfix_prefix) {
+            if let Some(master) = get("gitflow.branch.master") {
+                return Ok(master);
+            }
+            let master = ["main", "master"]
+                .into_iter()
+                .find(|name| self.find_branch_commit(name).is_ok())
+                .unwrap_or("master");
+            return Ok(master.to_string());
+        }
+
+        Ok(get("gitflow.branch.develop").unwrap_or_else(|| "develop".to_string()))
+    }
+
+    fn get_branch_merge_base(&self, branch_name: &str) -> Result<(String, git2::Oid)> {
+        let parent_branch = self.get_parent_branch(branch_name)?;
+        let parent_commit = self.find_branch_commit(&parent_branch).with_context(|| {
+            format!(
+                "Parent branch '{parent_branch}' not found. Set it with 'git config gitflow.branch.develop <branch>'."
+            )
+        })?;
+        let branch_commit = self.find_branch_commit(branch_name)?;
+
+        let merge_base = self
+            .repo
+            .merge_base(branch_commit.id(), parent_commit.id())
+            .with_context(|| {
+                format!("No common ancestor between '{branch_name}' and '{parent_branch}'")
+            })?;
+
+        Ok((parent_branch, merge_base))
+    }
+
+    fn find_branch_commit(&self, branch_name: &str) -> Result<git2::Commit<'_>> {
+        let candidates = [
+            format!("refs/heads/{branch_name}"),
+            format!("refs/remotes/origin/{branch_name}"),
+            format!("refs/remotes/{branch_name}"),
+        ];
+
+        for reference in &candidates {
+            if let Ok(reference) = self.repo.find_reference(reference) {
+                return Ok(reference.peel_to_commit()?);
+            }
+        }
+
+        anyhow::bail!("Branch '{branch_name}' not found");
+    }
+
+    fn is_description_only_commit(&self, commit: &git2::Commit) -> Result<bool> {
+        if commit.parent_count() != 1 {
+            return Ok(false);
+        }
+
+        let parent_tree = commit.parent(0)?.tree()?;
+        let diff = self
+            .repo
+            .diff_tree_to_tree(Some(&parent_tree), Some(&commit.tree()?), None)?;
+
+        Ok(diff.deltas().len() > 0
+            && diff
+                .deltas()
+                .all(|delta| delta.new_file().path() == Some(Path::new("BRANCHREADME.md"))))
+    }
+
+    pub fn get_github_issue_content(
+        &self,
+        issue_ref: &str,
+        ai_summarize: bool,
+        ai_timeout: u64,
+    ) -> Result<String> {
+        // Parse the issue reference - could be a URL, owner/repo#N, #N or a number
+        let (repo_slug, issue_number) = parse_github_issue_reference(issue_ref)?;
+        let repo_slug = match repo_slug {
+            Some(slug) => slug,
+            None => self.get_github_repo_slug()?,
+        };
+
+        // The API base URL is configurable for GitHub Enterprise and local mocks
+        let api_url = self
+            .get_config_value("GITHUB_API_URL", "branchdesc.githubApiUrl")
+            .unwrap_or_else(|| DEFAULT_GITHUB_API_URL.to_string());
+        let token = std::env::var("GITHUB_TOKEN")
+            .or_else(|_| std::env::var("GH_TOKEN"))
+            .ok();
+
+        let mut content =
+            fetch_github_issue(&api_url, &repo_slug, &issue_number, token.as_deref())?;
+
+        if ai_summarize {
+            content = self.ai_summarize_content(&content, ai_timeout)?;
+        }
+
+        Ok(content)
+    }
+
+    pub fn get_jira_issue_content(
+        &self,
+        issue_ref: &str,
+        ai_summarize: bool,
+        ai_timeout: u64,
+    ) -> Result<String> {
+        // Parse the issue reference - could be a key or a /browse/KEY URL
+        let (url_base, issue_key) = parse_jira_issue_reference(issue_ref)?;
+        let jira_url = url_base
+            .or_else(|| self.get_config_value("JIRA_URL", "branchdesc.jiraUrl"))
+            .context("Jira URL not configured. Set JIRA_URL or git config branchdesc.jiraUrl.")?;
+
+        // Jira Cloud uses user + API token, Jira Server/Data Center a personal access token
+        let user = self.get_config_value("JIRA_USER", "branchdesc.jiraUser");
+        let token = std::env::var("JIRA_API_TOKEN").ok();
+
+        let mut content =
+            fetch_jira_issue(&jira_url, &issue_key, user.as_deref(), token.as_deref())?;
+
+        if ai_summarize {
+            content = self.ai_summarize_content(&content, ai_timeout)?;
+        }
+
+        Ok(content)
+    }
+
+    fn get_github_repo_slug(&self) -> Result<String> {
+        if let Some(slug) = self.get_config_value("GITHUB_REPOSITORY", "branchdesc.githubRepo") {
+            return Ok(slug);
+        }
+
+        let remote = self
+            .repo
+            .find_remote("origin")
+            .context("No 'origin' remote found. Use owner/repo#N or set branchdesc.githubRepo.")?;
+        remote
+            .url()
+            .and_then(parse_github_repo_slug)
+            .context("Could not determine GitHub repository from 'origin' remote URL. Set branchdesc.githubRepo.")
+    }
+
+    /// Reads a setting from the environment first, then from git config.
+    fn get_config_value(&self, env_var: &str, config_key: &str) -> Option<String> {
+        if let Ok(value) = std::env::var(env_var)
+            && !value.trim().is_empty()
+        {
+            return Some(value);
+        }
+
+        self.repo
+            .config()
+            .ok()?
+            .get_string(config_key)
+            .ok()
+            .filter(|value| !value.trim().is_empty())
+    }
+
+    /// Reads a git-style boolean (true/false, yes/no, on/off, 1/0).
+    fn get_config_bool(&self, env_var: &str, config_key: &str) -> Result<Option<bool>> {
+        let Some(value) = self.get_config_value(env_var, config_key) else {
+            return Ok(None);
+        };
+        match value.trim().to_lowercase().as_str() {
+            "true" | "yes" | "on" | "1" => Ok(Some(true)),
+            "false" | "no" | "off" | "0" => Ok(Some(false)),
+            _ => anyhow::bail!("Invalid boolean '{value}' for {config_key}"),
+        }
+    }
+
+    fn get_editor_content(
+        &self,
+        target_branch: &str,
+        existing_description: &str,
+    ) -> Result<String> {
+        // Get list output for prefilled content
+        let list_content = self.get_list_content_for_editor()?;
+
+        let mut help =
+            format!("# Enter the description for branch '{target_branch}' above the line.\n");
+        help.push_str("# Markdown is allowed. An empty description aborts the edit.\n");
+        help.push_str("#\n");
+        help.push_str("# Current branch descriptions:\n");
+        for line in list_content.lines() {
+            help.push_str(&format!("# {}\n", line));
+        }
+
+        let description = self.edit_in_editor(
+            &format!("git-branch-desc-{}.md", target_branch.replace("/", "-")),
+            existing_description,
+            &help,
+        )?;
+
+        if description.is_empty() {
+            anyhow::bail!("Aborting due to empty description");
+        }
+        if description == existing_description.trim() {
+            anyhow::bail!("No description changes detected");
+        }
+
+        Ok(description)
+    }
+
+    /// Opens `text` in the configured editor and returns what is left above the scissors
+    /// line. `help` is shown below it and must consist of '#' lines.
+    fn edit_in_editor(&self, file_name: &str, text: &str, help: &str) -> Result<String> {
+        let temp_file = std::env::temp_dir().join(file_name);
+
+        // The text is editable as-is above the scissors line; all help text goes below it,
+        // so markdown headings starting with '#' are never mistaken for comments
+        let mut prefill_content = String::new();
+        if !text.trim().is_empty() {
+            prefill_content.push_str(text.trim_end());
+            prefill_content.push_str("\n\n");
+        }
+
+        prefill_content.push_str(EDITOR_SCISSORS_LINE);
+        prefill_content.push('\n');
+        prefill_content.push_str("# Do not modify or remove the line above.\n");
+        prefill_content.push_str("# Everything below it will be ignored.\n");
+        prefill_content.push_str("#\n");
+        prefill_content.push_str(help);
+
+        fs::write(&temp_file, &prefill_content)?;
+
+        let editor = self.get_editor_command();
+        let result = launch_editor(&editor, &temp_file)
+            .and_then(|_| fs::read_to_string(&temp_file).context("Failed to read edited content"));
+
+        // Clean up temp file
+        let _ = fs::remove_file(&temp_file);
+
+        Ok(parse_editor_content(&result?))
+    }
+
+    /// Resolves the editor like git does: GIT_EDITOR, core.editor, VISUAL (unless the terminal
+    /// is dumb), EDITOR, then the platform default.
+    pub fn get_editor_command(&self) -> String {
+        if let Some(editor) = self.get_config_value("GIT_EDITOR", "core.editor") {
+            return editor;
+        }
+
+        let terminal_is_dumb = std::env::var("TERM").is_ok_and(|term| term == "dumb");
+        let from_env = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        let editor = if terminal_is_dumb {
+            None
+        } else {
+            from_env("VISUAL")
+        };
+
+        editor.or_else(|| from_env("EDITOR")).unwrap_or_else(|| {
+            if cfg!(target_os = "windows") {
+                "notepad.exe".to_string()
+            } else {
+                "nano".to_string()
+            }
+        })
+    }
+
+    fn get_list_content_for_editor(&self) -> Result<String> {
+        let mut output = Vec::new();
+        let mut tw = TabWriter::new(&mut output);
+
+        let mut descriptions = Vec::new();
+        let local_branches = self.get_local_branch_list()?;
+        let mut processed_branches = HashSet::new();
 
-        let client = Client::new();
-        let request_body = serde_json::json!({
-            "model": "llama3.2:1b",
-            "stream": false,
-            "messages": [
+        // Process remote branches first
+        let remotes = self.repo.remotes()?;
+        for remote_name in remotes.iter().flatten() {
+            let remote_branches = self.repo.branches(Some(git2::BranchType::Remote))?;
+            for branch in remote_branches {
+                let (branch, _) = branch.context("Failed to get branch")?;
+                if let Some(name) = branch.name().context("Failed to get branch name")?
+                    && name.starts_with(&format!("{remote_name}/"))
                 {
-                    "role": "system",
-                    "content": system_prompt
-                },
+                    let branch_name = name.strip_prefix(&format!("{remote_name}/")).unwrap();
+                    if let Ok(Some(desc)) = self.read_branch_description_from_git(name)
+                        && let (desc, _) = split_description_metadata(&desc)
+                        && !desc.trim().is_empty()
+                    {
+                        let truncated = if desc.len() > 50 {
+                            format!("{}...", &desc[..47])
+                        } else {
+                            desc.clone()
+                        };
+                        descriptions.push(BranchDescription {
+                            branch: format!("{} (remote)", branch_name),
+                            description: truncated,
+                        });
+                        processed_branches.insert(branch_name.to_string());
+                    }
+                }
+            }
+        }
+
+        // Process local branches
+        for branch_name in local_branches {
+            if processed_branches.contains(&branch_name) {
+                continue;
+            }
+            if let Ok(Some(desc)) = self.read_branch_description_from_git(&branch_name)
+                && let (desc, _) = split_description_metadata(&desc)
+                && !desc.trim().is_empty()
+            {
+                let truncated = if desc.len() > 50 {
+                    format!("{}...", &desc[..47])
+                } else {
+                    desc.clone()
+                };
+                descriptions.push(BranchDescription {
+                    branch: branch_name,
+                    description: truncated,
+                });
+            }
+        }
+
+        // Write table headers
+        writeln!(tw, "Branch\tDescription")?;
+        writeln!(tw, "------\t-----------")?;
+
+        // Write descriptions
+        for desc in descriptions {
+            writeln!(tw, "{}\t{}", desc.branch, desc.description)?;
+        }
+
+        tw.flush()?;
+        Ok(String::from_utf8_lossy(&output).to_string())
+    }
+
+    /// Resolves the AI provider settings. Each value is taken from the first of: environment
+    /// variable (`BRANCHDESC_AI_*`), `git config branchdesc.ai.*`, `.branchdesc.toml` in the
+    /// repository, the user config file.
+    pub fn ai_settings(&self) -> Result<AiSettings> {
+        let config = FileConfig::load(self.repo.workdir())?;
+        let file = config.ai;
+
+        let provider = self
+            .get_config_value("BRANCHDESC_AI_PROVIDER", "branchdesc.ai.provider")
+            .or(file.provider);
+        let provider = match provider {
+            Some(name) => ProviderKind::parse(&name)?,
+            None => ProviderKind::Ollama,
+        };
+
+        let summarizer = match self.ai_overrides.summarizer {
+            Some(summarizer) => summarizer,
+            None => match self
+                .get_config_value("BRANCHDESC_AI_SUMMARIZER", "branchdesc.ai.summarizer")
+                .or(file.summarizer)
+            {
+                Some(name) => SummarizerKind::parse(&name)?,
+                None => SummarizerKind::Auto,
+            },
+        };
+
+        let temperature =
+            match self.get_config_value("BRANCHDESC_AI_TEMPERATURE", "branchdesc.ai.temperature") {
+                Some(value) => Some(
+                    value
+                        .trim()
+                        .parse()
+                        .with_context(|| format!("Invalid AI temperature '{value}'"))?,
+                ),
+                None => file.temperature,
+            };
+
+        let chunk_tokens = match self
+            .get_config_value("BRANCHDESC_AI_CHUNK_TOKENS", "branchdesc.ai.chunkTokens")
+        {
+            Some(value) => value
+                .trim()
+                .parse()
+                .ok()
+                .filter(|&tokens: &usize| tokens > 0)
+                .with_context(|| format!("Invalid AI chunk token budget '{value}'"))?,
+            None => file.chunk_tokens.unwrap_or(ai::DEFAULT_CHUNK_TOKENS),
+        };
+
+        let mut api_key = self
+            .get_config_value("BRANCHDESC_AI_API_KEY", "branchdesc.ai.apiKey")
+            .or(file.api_key);
+        if api_key.is_none() && provider == ProviderKind::OpenAi {
+            api_key = std::env::var("OPENAI_API_KEY").ok();
+        }
+
+        Ok(AiSettings {
+            provider,
+            summarizer,
+            endpoint: self
+                .get_config_value("BRANCHDESC_AI_ENDPOINT", "branchdesc.ai.endpoint")
+                .or(file.endpoint),
+            model: self
+                .get_config_value("BRANCHDESC_AI_MODEL", "branchdesc.ai.model")
+                .or(file.model),
+            temperature,
+            api_key,
+            command: self
+                .get_config_value("BRANCHDESC_AI_COMMAND", "branchdesc.ai.command")
+                .or(file.command),
+            chunk_tokens,
+            prompt: self
+                .ai_overrides
+                .prompt
+                .clone()
+                .or_else(|| self.get_config_value("BRANCHDESC_AI_PROMPT", "branchdesc.ai.prompt"))
+                .or(file.prompt),
+            language: self
+                .get_config_value("BRANCHDESC_AI_LANGUAGE", "branchdesc.ai.language")
+                .or(file.language),
+            length: self
+                .get_config_value("BRANCHDESC_AI_LENGTH", "branchdesc.ai.length")
+                .or(file.length),
+            prompts: config.prompts,
+        })
+    }
+
+    /// AI summary for a description that is about to be written. In a terminal the result
+    /// can be accepted, regenerated or edited first.
+    fn ai_summarize_for_description(
+        &self,
+        content: &str,
+        vars: &PromptVars,
+        timeout_seconds: u64,
+    ) -> Result<String> {
+        let mut use_cache = true;
+        loop {
+            let summary = self.summarize_with_prompt(content, vars, timeout_seconds, use_cache)?;
+            if !io::stdin().is_terminal() {
+                return Ok(summary);
+            }
+
+            print!("Use this summary? [A]ccept, [r]egenerate, [e]dit: ");
+            io::stdout().flush()?;
+            let mut answer = String::new();
+            io::stdin().read_line(&mut answer)?;
+
+            match answer.trim().to_lowercase().as_str() {
+                "" | "a" | "accept" | "y" | "yes" => return Ok(summary),
+                "r" | "regenerate" => use_cache = false,
+                "e" | "edit" => {
+                    let edited = self.edit_in_editor(
+                        "git-branch-desc-summary.md",
+                        &summary,
+                        "# Edit the AI summary above the line.\n",
+                    )?;
+                    if edited.is_empty() {
+                        anyhow::bail!("Aborting due to empty description");
+                    }
+                    return Ok(edited);
+                }
+                other => println!("Unknown choice '{other}'"),
+            }
+        }
+    }
+
+    pub fn ai_summarize_content(&self, content: &str, timeout_seconds: u64) -> Result<String> {
+        let vars = PromptVars {
+            branch: self.get_current_branch().unwrap_or_default(),
+            issue_title: None,
+        };
+        self.ai_summarize_with_prompt(content, &vars, timeout_seconds)
+    }
+
+    /// Summarizes content with the configured prompt template, see `AiSettings::system_prompt`.
+    pub fn ai_summarize_with_prompt(
+        &self,
+        content: &str,
+        vars: &PromptVars,
+        timeout_seconds: u64,
+    ) -> Result<String> {
+        self.summarize_with_prompt(content, vars, timeout_seconds, true)
+    }
+
+    /// Looks the summary up in the cache unless `use_cache` is false or `--no-cache` was
+    /// given; generated summaries are always stored for the next run.
+    fn summarize_with_prompt(
+        &self,
+        content: &str,
+        vars: &PromptVars,
+        timeout_seconds: u64,
+        use_cache: bool,
+    ) -> Result<String> {
+        let settings = self.ai_settings()?;
+        if settings.summarizer == SummarizerKind::Builtin {
+            return builtin_summary(content, vars);
+        }
+
+        let system_prompt = settings.system_prompt(ai::is_git_diff(content), vars)?;
+        let cache = SummaryCache::for_repo(&self.repo);
+        // Everything that changes the answer: another server, model or temperature must not
+        // return this summary
+        let provider_name = settings.create_provider()?.name();
+        let endpoint = settings.endpoint.clone().unwrap_or_default();
+        let model = settings.model.clone().unwrap_or_default();
+        let temperature = settings
+            .temperature
+            .map(|temperature| temperature.to_string())
+            .unwrap_or_default();
+        let chunk_tokens = settings.chunk_tokens.to_string();
+        let key = SummaryCache::key(&[
+            &provider_name,
+            &endpoint,
+            &model,
+            &temperature,
+            &chunk_tokens,
+            &system_prompt,
+            content,
+        ])?;
+
+        if use_cache
+            && !self.ai_overrides.no_cache
+            && let Some(summary) = cache.get(&key)
+        {
+            eprintln!("💾 Using cached AI summary (--no-cache to regenerate)");
+            println!("{summary}");
+            return Ok(summary);
+        }
+
+        match self.generate_with_ai(&settings, &system_prompt, content, timeout_seconds, true) {
+            Ok(summary) => {
+                // A dry run must not leave anything behind, not even in the cache
+                if !self.dry_run
+                    && let Err(err) = cache.put(&key, &summary)
                 {
-                    "role": "user",
-                    "content": user_prompt
+                    eprintln!("⚠️  Could not cache AI summary: {err:#}");
                 }
-            ]
-        });
+                Ok(summary)
+            }
+            Err(err) if settings.summarizer == SummarizerKind::Auto && ai::is_unreachable(&err) => {
+                eprintln!("⚠️  {err:#}");
+                eprintln!(
+                    "💡 Falling back to the built-in summarizer (--summarizer=ai to fail instead)"
+                );
+                builtin_summary(content, vars)
+            }
+            Err(err) => Err(err),
+        }
+    }
 
+    /// Removes cached AI summaries older than `max_age`, or all of them.
+    pub fn prune_ai_cache(&self, max_age: Option<std::time::Duration>) -> Result<()> {
+        let stats = SummaryCache::for_repo(&self.repo).prune(max_age)?;
         println!(
-            "🤖 Generating AI summary (timeout: {}s)...",
-            timeout_seconds
+            "✅ Removed {} cached AI summaries ({:.1} KiB)",
+            stats.entries,
+            stats.bytes as f64 / 1024.0
         );
+        Ok(())
+    }
 
-        let response = client
-            .post("http://localhost:11434/api/chat")
-            .timeout(std::time::Duration::from_secs(timeout_seconds))
-            .json(&request_body)
-            .send()
-            .context(
-                "Failed to connect to Ollama. Make sure Ollama is running locally on port 11434.",
-            )?;
+    /// Runs the configured provider; progress goes to stderr, the answer is printed live to
+    /// stdout with `live_preview`.
+    fn generate_with_ai(
+        &self,
+        settings: &AiSettings,
+        system_prompt: &str,
+        content: &str,
+        timeout_seconds: u64,
+        live_preview: bool,
+    ) -> Result<String> {
+        let provider = settings.create_provider()?;
+        eprintln!(
+            "🤖 Generating AI summary with {} (timeout: {}s)...",
+            provider.name(),
+            timeout_seconds
+        );
 
-        if !response.status().is_success() {
-            anyhow::bail!(
-                "Ollama API request failed with status: {}",
-                response.status()
-            );
+        // Content above the token budget is summarized in parts first (map-reduce)
+        let mut stdout = io::stdout();
+        let summary = ai::summarize(
+            provider.as_ref(),
+            content,
+            system_prompt,
+            settings.chunk_tokens,
+            std::time::Duration::from_secs(timeout_seconds),
+            &mut |token| {
+                if live_preview {
+                    let _ = write!(stdout, "{token}");
+                    let _ = stdout.flush();
+                }
+            },
+        );
+        if live_preview {
+            println!();
         }
+        let summary = summary?;
 
-        let response_json: Value = response
-            .json()
-            .context("Failed to parse Ollama response as JSON")?;
-
-        let summary = response_json["message"]["content"]
-            .as_str()
-            .context("Failed to extract content from Ollama response")?;
-
-        let cleaned_summary = clean_ai_preamble(summary);
+        let cleaned_summary = clean_ai_preamble(&summary);
 
         if cleaned_summary.trim().is_empty() {
             anyhow::bail!(
@@ -754,13 +2320,14 @@ impl GitBranchDescManager {
     ) -> Option<BranchDescription> {
         let branch_name = full_branch_name;
 
-        if let Ok(Some(description)) = self.read_branch_description_from_git(full_branch_name) {
-            if !description.trim().is_empty() {
-                return Some(BranchDescription {
-                    branch: branch_name.to_string(),
-                    description: description.trim().to_string(),
-                });
-            }
+        if let Ok(Some(description)) = self.read_branch_description_from_git(full_branch_name)
+            && let (description, _) = split_description_metadata(&description)
+            && !description.trim().is_empty()
+        {
+            return Some(BranchDescription {
+                branch: branch_name.to_string(),
+                description: description.trim().to_string(),
+            });
         }
 
         if include_all {
@@ -785,49 +2352,21 @@ impl GitBranchDescManager {
         let remote_branches = self.repo.branches(Some(git2::BranchType::Remote))?;
         for branch in remote_branches {
             let (branch, _) = branch.context("Failed to get branch")?;
-            if let Some(name) = branch.name().context("Failed to get branch name")? {
-                if let Some(short_name) = name.strip_prefix("origin/") {
-                    branches.push(format!("  {short_name} (remote)"));
-                }
+            if let Some(name) = branch.name().context("Failed to get branch name")?
+                && let Some(short_name) = name.strip_prefix("origin/")
+            {
+                branches.push(format!("  {short_name} (remote)"));
             }
         }
 
         Ok(branches.join("\n"))
     }
 
-    fn push_current_branch(&self, branch_name: &str) -> Result<()> {
-        let output = Command::new("git")
-            .args(["push", "origin", branch_name])
-            .output()
-            .context("Failed to execute git push command")?;
-
-        if output.status.success() {
-            println!("✅ Pushed changes to remote branch '{branch_name}'");
-        } else {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("Failed to push branch: {error_msg}");
-        }
-
-        Ok(())
-    }
-
-    fn push_branch(&self, branch_name: &str) -> Result<()> {
-        let output = Command::new("git")
-            .args([
-                "push",
-                "origin",
-                &format!("refs/heads/{branch_name}:refs/heads/{branch_name}"),
-            ])
-            .output()
-            .context("Failed to execute git push command")?;
-
-        if output.status.success() {
-            println!("✅ Pushed changes to remote branch '{branch_name}'");
-        } else {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("Failed to push branch: {error_msg}");
-        }
-
+    /// Pushes the branch to `origin` through libgit2, see `push::push_branch` for the
+    /// credentials used and the errors returned.
+    pub fn push_branch(&self, branch_name: &str) -> Result<()> {
+        push::push_branch(&self.repo, "origin", branch_name, self.force_with_lease)?;
+        println!("✅ Pushed changes to remote branch '{branch_name}'");
         Ok(())
     }
 
@@ -869,6 +2408,8 @@ impl GitBranchDescManager {
             .find_reference(&format!("refs/heads/{branch_name}"))
         {
             branch_ref
+        } else if let Some(branch_ref) = self.remote_tracking_ref(branch_name) {
+            branch_ref
         } else if let Ok(branch_ref) = self
             .repo
             .find_reference(&format!("refs/remotes/{branch_name}"))
@@ -878,135 +2419,783 @@ impl GitBranchDescManager {
             return Ok(None);
         };
 
-        let commit = branch_ref.peel_to_commit()?;
-        let tree = commit.tree()?;
+        let commit = branch_ref.peel_to_commit()?;
+        let tree = commit.tree()?;
+
+        match tree.get_name("BRANCHREADME.md") {
+            Some(entry) => {
+                let blob = self.repo.find_blob(entry.id())?;
+                let content = String::from_utf8_lossy(blob.content());
+                Ok(Some(content.to_string()))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+// Utility functions
+pub fn get_terminal_width() -> usize {
+    if let Some((Width(w), _)) = terminal_size() {
+        (w as usize * 90) / 100 // Use 90% of terminal width
+    } else {
+        80 // Default width
+    }
+}
+
+pub fn wrap_text(text: &str, max_width: usize) -> String {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let mut lines = Vec::new();
+    let mut current_line = String::new();
+
+    for word in words {
+        // If adding this word would exceed the max width, start a new line
+        if !current_line.is_empty() && current_line.len() + 1 + word.len() > max_width {
+            lines.push(current_line.clone());
+            current_line = word.to_string();
+        } else {
+            if !current_line.is_empty() {
+                current_line.push(' ');
+            }
+            current_line.push_str(word);
+        }
+    }
+
+    if !current_line.is_empty() {
+        lines.push(current_line);
+    }
+
+    lines.join("\n")
+}
+
+pub fn clean_ai_preamble(text: &str) -> String {
+    let text = text.trim();
+
+    // Filter out lines with thinking tags and empty lines
+    let filtered_lines: Vec<&str> = text
+        .lines()
+        .filter(|line| {
+            let trimmed = line.trim();
+            !trimmed.is_empty() && !trimmed.contains("<think>") && !trimmed.contains("</think>")
+        })
+        .collect();
+
+    let mut result = filtered_lines.join("\n");
+
+    // Remove common AI preambles
+    let preambles_to_remove = [
+        "Here's a concise branch description:",
+        "Here is a concise branch description:",
+        "Here's a branch description:",
+        "Here is a branch description:",
+        "Here's the branch description:",
+        "Here is the branch description:",
+        "A concise branch description:",
+        "Branch description:",
+        "Description:",
+        "Summary:",
+        "Here's a summary:",
+        "Here is a summary:",
+        "Based on the content:",
+        "Based on this content:",
+        "Description for this branch:",
+        "A brief description:",
+        "Brief description:",
+    ];
+
+    for preamble in &preambles_to_remove {
+        if result
+            .trim_start()
+            .to_lowercase()
+            .starts_with(&preamble.to_lowercase())
+        {
+            result = result
+                .trim_start()
+                .strip_prefix(preamble)
+                .unwrap_or(&result)
+                .trim_start()
+                .to_string();
+        }
+    }
+
+    // Remove surrounding quotes if present
+    let result = result.trim();
+    if (result.starts_with('"') && result.ends_with('"'))
+        || (result.starts_with('\'') && result.ends_with('\''))
+    {
+        result[1..result.len() - 1].trim().to_string()
+    } else {
+        result.to_string()
+    }
+}
+
+/// The repository's main `.git` directory, shared by all of its worktrees.
+pub fn common_dir(repo: &Repository) -> PathBuf {
+    // Linked worktrees point to it from their own git directory
+    match fs::read_to_string(repo.path().join("commondir")) {
+        Ok(common_dir) if repo.is_worktree() => repo.path().join(common_dir.trim()),
+        _ => repo.path().to_path_buf(),
+    }
+}
+
+fn print_commit_result(branch_name: &str, amended: bool) {
+    if amended {
+        println!("✅ Amended the description commit on branch '{branch_name}'");
+    } else {
+        println!("✅ Committed changes to branch '{branch_name}'");
+    }
+}
+
+/// Extractive summary for machines without a model, printed like a generated one.
+fn builtin_summary(content: &str, vars: &PromptVars) -> Result<String> {
+    eprintln!("📝 Summarizing with the built-in extractive summarizer...");
+    let summary = extractive::summarize(content, vars.issue_title.as_deref());
+    if summary.trim().is_empty() {
+        anyhow::bail!("Nothing to summarize. Please provide description manually.");
+    }
+    println!("{summary}");
+    Ok(summary)
+}
+
+/// Formats a diff as a unified patch, leaving out the description file.
+fn format_patch(diff: &git2::Diff) -> Result<String> {
+    let mut content = String::new();
+    diff.print(git2::DiffFormat::Patch, |delta, _hunk, line| {
+        if delta.new_file().path() == Some(Path::new("BRANCHREADME.md")) {
+            return true;
+        }
+        if matches!(line.origin(), '+' | '-' | ' ') {
+            content.push(line.origin());
+        }
+        content.push_str(&String::from_utf8_lossy(line.content()));
+        true
+    })
+    .context("Failed to format diff")?;
+    Ok(content)
+}
+
+/// Removes a Markdown code fence the model wrapped its whole answer in.
+pub fn strip_code_fence(text: &str) -> String {
+    let trimmed = text.trim();
+    if let Some(inner) = trimmed.strip_prefix("```")
+        && let Some(inner) = inner.strip_suffix("```")
+    {
+        // Drop the info string of the opening fence, e.g. ```markdown
+        let inner = inner.split_once('\n').map_or("", |(_, rest)| rest);
+        return inner.trim().to_string();
+    }
+    trimmed.to_string()
+}
+
+fn run_glab(args: &[&str]) -> Result<String> {
+    let output = Command::new("glab")
+        .args(args)
+        .output()
+        .context("Failed to execute glab command. Make sure glab is installed and configured.")?;
+
+    if !output.status.success() {
+        let error_msg = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("glab command failed: {error_msg}");
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Splits a GitLab issue or merge request URL into its kind and number.
+fn parse_gitlab_url(reference: &str) -> Result<Option<(String, String)>> {
+    // Supports nested groups: https://host/group/subgroup/project/-/issues/N
+    let url_regex = Regex::new(r"https?://[^/]+/.+/-/(issues|merge_requests)/(\d+)")
+        .context("Failed to compile URL regex")?;
+
+    Ok(url_regex
+        .captures(reference)
+        .map(|captures| (captures[1].to_string(), captures[2].to_string())))
+}
+
+/// Runs the editor command with the file appended. Like git, the command goes through the
+/// shell so it may carry arguments (`code --wait`); on Windows it is split on whitespace
+/// honouring double quotes.
+fn launch_editor(editor: &str, file: &Path) -> Result<()> {
+    let status = if cfg!(target_os = "windows") {
+        let mut parts = split_command_line(editor);
+        if parts.is_empty() {
+            anyhow::bail!("Editor command is empty");
+        }
+        let program = parts.remove(0);
+        Command::new(program).args(parts).arg(file).status()
+    } else {
+        Command::new("sh")
+            .arg("-c")
+            .arg(format!("{editor} \"$@\""))
+            .arg(editor)
+            .arg(file)
+            .status()
+    }
+    .with_context(|| format!("Failed to open editor '{editor}'"))?;
+
+    if !status.success() {
+        anyhow::bail!("Editor exited with non-zero status");
+    }
+
+    Ok(())
+}
+
+/// Splits a command line on whitespace, keeping double-quoted parts together.
+pub fn split_command_line(command: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut has_part = false;
+
+    for c in command.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                has_part = true;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if has_part {
+                    parts.push(std::mem::take(&mut current));
+                    has_part = false;
+                }
+            }
+            c => {
+                current.push(c);
+                has_part = true;
+            }
+        }
+    }
+
+    if has_part {
+        parts.push(current);
+    }
+
+    parts
+}
+
+/// Extracts the description from an edited template: everything from the scissors line on is
+/// dropped, trailing whitespace is removed and consecutive blank lines are collapsed.
+pub fn parse_editor_content(content: &str) -> String {
+    let mut lines = Vec::new();
+    for line in content.lines() {
+        if line.trim_end() == EDITOR_SCISSORS_LINE {
+            break;
+        }
+        let line = line.trim_end();
+        if line.is_empty() && lines.last().is_none_or(|last: &&str| last.is_empty()) {
+            continue;
+        }
+        lines.push(line);
+    }
+
+    lines.join("\n").trim_end().to_string()
+}
+
+/// Decodes text files written by Windows editors: strips UTF-8/UTF-16 BOMs, detects BOM-less
+/// UTF-16 and falls back to Windows-1252 for bytes that are not valid UTF-8.
+pub fn decode_text(bytes: &[u8]) -> String {
+    let text = if let Some(rest) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+        String::from_utf8_lossy(rest).to_string()
+    } else if let Some(rest) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+        decode_utf16(rest, u16::from_le_bytes)
+    } else if let Some(rest) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        decode_utf16(rest, u16::from_be_bytes)
+    } else if looks_like_utf16(bytes, 1) {
+        decode_utf16(bytes, u16::from_le_bytes)
+    } else if looks_like_utf16(bytes, 0) {
+        decode_utf16(bytes, u16::from_be_bytes)
+    } else if let Ok(text) = std::str::from_utf8(bytes) {
+        text.to_string()
+    } else {
+        decode_windows_1252(bytes)
+    };
+
+    text.replace("\r\n", "\n")
+}
+
+fn looks_like_utf16(bytes: &[u8], zero_offset: usize) -> bool {
+    // ASCII text in UTF-16 has a zero in every other byte
+    bytes.len() >= 2
+        && bytes.len().is_multiple_of(2)
+        && bytes
+            .chunks(2)
+            .filter(|pair| pair[zero_offset] == 0)
+            .count()
+            * 2
+            > bytes.len() / 2
+}
+
+fn decode_utf16(bytes: &[u8], to_unit: fn([u8; 2]) -> u16) -> String {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| to_unit([pair[0], pair[1]]))
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+fn decode_windows_1252(bytes: &[u8]) -> String {
+    // Windows-1252 matches Latin-1 except for 0x80-0x9F
+    const HIGH: [char; 32] = [
+        '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8D}', 'Ž',
+        '\u{8F}', '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9D}',
+        'ž', 'Ÿ',
+    ];
+    bytes
+        .iter()
+        .map(|&byte| match byte {
+            0x80..=0x9F => HIGH[(byte - 0x80) as usize],
+            _ => byte as char,
+        })
+        .collect()
+}
+
+pub fn extract_issue_from_branch(branch_name: &str, pattern: &str) -> Result<Option<String>> {
+    let issue_regex = Regex::new(pattern)
+        .with_context(|| format!("Invalid issue pattern '{pattern}' in branchdesc.issuePattern"))?;
+
+    Ok(issue_regex.captures(branch_name).map(|captures| {
+        captures
+            .get(1)
+            .unwrap_or_else(|| captures.get(0).unwrap())
+            .as_str()
+            .to_string()
+    }))
+}
+
+static METADATA_LINE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^<!--\s*([\w-]+):\s*(.*?)\s*-->$").unwrap());
+
+/// Splits trailing `<!-- key: value -->` metadata lines off a stored description.
+pub fn split_description_metadata(content: &str) -> (String, BTreeMap<String, String>) {
+    let mut metadata = BTreeMap::new();
+    let mut lines: Vec<&str> = content.trim_end().lines().collect();
+
+    while let Some(line) = lines.last() {
+        match METADATA_LINE.captures(line.trim()) {
+            Some(captures) => {
+                metadata.insert(captures[1].to_string(), captures[2].to_string());
+                lines.pop();
+            }
+            None => break,
+        }
+    }
+
+    if metadata.is_empty() {
+        return (content.to_string(), metadata);
+    }
+
+    (lines.join("\n").trim_end().to_string(), metadata)
+}
+
+/// Appends metadata as HTML comments so it stays invisible in rendered markdown.
+pub fn join_description_metadata(body: &str, metadata: &BTreeMap<String, String>) -> String {
+    if metadata.is_empty() {
+        return body.to_string();
+    }
+
+    let metadata_lines: Vec<String> = metadata
+        .iter()
+        .map(|(key, value)| format!("<!-- {key}: {value} -->"))
+        .collect();
+
+    format!("{}\n\n{}", body.trim_end(), metadata_lines.join("\n"))
+}
+
+pub fn parse_issue_reference(issue_ref: &str) -> Result<String> {
+    // Check if it's a GitLab issue URL (supports nested groups)
+    if let Some((kind, number)) = parse_gitlab_url(issue_ref)?
+        && kind == "issues"
+    {
+        return Ok(number);
+    }
+
+    // Check if it's just a number
+    if !issue_ref.is_empty() && issue_ref.chars().all(|c| c.is_ascii_digit()) {
+        return Ok(issue_ref.to_string());
+    }
+
+    anyhow::bail!(
+        "Invalid issue reference: '{}'. Expected issue number or GitLab issue URL.",
+        issue_ref
+    );
+}
+
+pub fn parse_merge_request_reference(mr_ref: &str) -> Result<String> {
+    // Check if it's a GitLab merge request URL (supports nested groups)
+    if let Some((kind, number)) = parse_gitlab_url(mr_ref)?
+        && kind == "merge_requests"
+    {
+        return Ok(number);
+    }
+
+    // Check if it's a number, optionally in GitLab's !N notation
+    let number = mr_ref.strip_prefix('!').unwrap_or(mr_ref);
+    if !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()) {
+        return Ok(number.to_string());
+    }
+
+    anyhow::bail!(
+        "Invalid merge request reference: '{}'. Expected MR number, !N or GitLab merge request URL.",
+        mr_ref
+    );
+}
+
+pub fn parse_merge_request_list_json(json: &str) -> Result<Option<String>> {
+    let parsed: Value =
+        serde_json::from_str(json).context("Failed to parse JSON output from glab")?;
+
+    let merge_requests = parsed
+        .as_array()
+        .context("Expected a list of merge requests from glab")?;
+
+    Ok(merge_requests
+        .iter()
+        .find(|mr| mr["state"].as_str().is_none_or(|state| state == "opened"))
+        .and_then(|mr| mr["iid"].as_u64())
+        .map(|iid| iid.to_string()))
+}
+
+/// Parses the discussions printed by `glab api --paginate`: one JSON list per page.
+pub fn parse_merge_request_discussions_json(json: &str) -> Result<String> {
+    let mut discussions = Vec::new();
+    for page in serde_json::Deserializer::from_str(json).into_iter::<Value>() {
+        let page = page.context("Failed to parse discussions JSON from glab")?;
+        match page {
+            Value::Array(page) => discussions.extend(page),
+            _ => anyhow::bail!("Expected a list of discussions from glab"),
+        }
+    }
+
+    // Skip system notes like "added 1 commit" or "changed the description"
+    let mut lines = Vec::new();
+    for note in discussions
+        .iter()
+        .filter_map(|discussion| discussion["notes"].as_array())
+        .flatten()
+        .filter(|note| !note["system"].as_bool().unwrap_or(false))
+    {
+        let body = note["body"].as_str().unwrap_or("").trim();
+        if body.is_empty() {
+            continue;
+        }
+        let author = note["author"]["name"].as_str().unwrap_or("unknown");
+        lines.push(format!("- {author}: {}", body.replace('\n', "\n  ")));
+    }
+
+    Ok(lines.join("\n"))
+}
+
+// GitHub and GitHub Enterprise issue/PR URLs: https://host/owner/repo/issues/N or /pull/N
+static GITHUB_URL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^https?://[^/]+/([^/]+/[^/]+)/(?:issues|pull)/(\d+)/?$").unwrap()
+});
+// Short references: owner/repo#N, #N or N
+static GITHUB_SHORT_REFERENCE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?:([\w.-]+/[\w.-]+)#|#)?(\d+)$").unwrap());
+// Handles https://host/owner/repo(.git), ssh://git@host/owner/repo.git and git@host:owner/repo.git
+static GITHUB_REMOTE_URL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[/:]([^/:]+)/([^/]+?)(?:\.git)?/?$").unwrap());
+
+pub fn parse_github_issue_reference(issue_ref: &str) -> Result<(Option<String>, String)> {
+    if let Some(captures) = GITHUB_URL.captures(issue_ref) {
+        return Ok((Some(captures[1].to_string()), captures[2].to_string()));
+    }
+
+    if let Some(captures) = GITHUB_SHORT_REFERENCE.captures(issue_ref) {
+        let repo_slug = captures.get(1).map(|slug| slug.as_str().to_string());
+        return Ok((repo_slug, captures[2].to_string()));
+    }
+
+    anyhow::bail!(
+        "Invalid GitHub reference: '{}'. Expected #N, owner/repo#N, issue number or GitHub issue/PR URL.",
+        issue_ref
+    );
+}
+
+pub fn parse_github_repo_slug(remote_url: &str) -> Option<String> {
+    let captures = GITHUB_REMOTE_URL.captures(remote_url)?;
+    Some(format!("{}/{}", &captures[1], &captures[2]))
+}
+
+pub fn fetch_github_issue(
+    api_url: &str,
+    repo_slug: &str,
+    issue_number: &str,
+    token: Option<&str>,
+) -> Result<String> {
+    // The issues endpoint serves pull requests as well
+    let url = format!(
+        "{}/repos/{}/issues/{}",
+        api_url.trim_end_matches('/'),
+        repo_slug,
+        issue_number
+    );
+
+    let client = Client::new();
+    let mut request = client
+        .get(&url)
+        .header("Accept", "application/vnd.github+json")
+        .header("User-Agent", "git-branch-desc");
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+
+    let response = request
+        .send()
+        .with_context(|| format!("Failed to connect to GitHub API at {api_url}"))?;
+
+    if !response.status().is_success() {
+        anyhow::bail!(
+            "GitHub API request for {repo_slug}#{issue_number} failed with status: {}",
+            response.status()
+        );
+    }
+
+    let json = response
+        .text()
+        .context("Failed to read GitHub API response")?;
+    parse_github_issue_json(&json)
+}
+
+pub fn parse_github_issue_json(json: &str) -> Result<String> {
+    let parsed: Value =
+        serde_json::from_str(json).context("Failed to parse JSON response from GitHub")?;
+
+    let title = parsed["title"]
+        .as_str()
+        .context("Could not extract issue title from GitHub response")?;
+
+    // GitHub uses "body" and returns null for empty bodies
+    let body = parsed["body"].as_str().unwrap_or("").trim();
+
+    let result = if body.is_empty() {
+        title.to_string()
+    } else {
+        format!("{}\n\n{}", title, body)
+    };
+
+    Ok(result)
+}
+
+static JIRA_KEY: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^[A-Z][A-Z0-9_]+-\d+$").unwrap());
+// Keys are embedded in branch names like feature/PROJ-123-login
+static JIRA_KEY_IN_TEXT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:^|[^A-Za-z0-9])([A-Z][A-Z0-9_]+-\d+)").unwrap());
+// Jira browse URLs: https://jira.example.com/browse/PROJ-123
+static JIRA_URL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(https?://.+?)/browse/([A-Z][A-Z0-9_]+-\d+)/?$").unwrap());
+
+pub fn is_jira_key(issue_ref: &str) -> bool {
+    JIRA_KEY.is_match(issue_ref)
+}
 
-        match tree.get_name("BRANCHREADME.md") {
-            Some(entry) => {
-                let blob = self.repo.find_blob(entry.id())?;
-                let content = String::from_utf8_lossy(blob.content());
-                Ok(Some(content.to_string()))
-            }
-            None => Ok(None),
-        }
-    }
+pub fn find_jira_key(text: &str) -> Option<String> {
+    JIRA_KEY_IN_TEXT
+        .captures(text)
+        .map(|captures| captures[1].to_string())
 }
 
-// Utility functions
-pub fn get_terminal_width() -> usize {
-    if let Some((Width(w), _)) = terminal_size() {
-        (w as usize * 90) / 100 // Use 90% of terminal width
-    } else {
-        80 // Default width
+pub fn parse_jira_issue_reference(issue_ref: &str) -> Result<(Option<String>, String)> {
+    if let Some(captures) = JIRA_URL.captures(issue_ref) {
+        return Ok((Some(captures[1].to_string()), captures[2].to_string()));
+    }
+
+    if is_jira_key(issue_ref) {
+        return Ok((None, issue_ref.to_string()));
     }
+
+    anyhow::bail!(
+        "Invalid Jira reference: '{}'. Expected issue key (PROJ-123) or Jira browse URL.",
+        issue_ref
+    );
 }
 
-pub fn wrap_text(text: &str, max_width: usize) -> String {
-    let words: Vec<&str> = text.split_whitespace().collect();
-    let mut lines = Vec::new();
-    let mut current_line = String::new();
+pub fn fetch_jira_issue(
+    jira_url: &str,
+    issue_key: &str,
+    user: Option<&str>,
+    token: Option<&str>,
+) -> Result<String> {
+    // API v2 returns the description as wiki markup (v3 would return ADF JSON)
+    let url = format!(
+        "{}/rest/api/2/issue/{}?fields=summary,description",
+        jira_url.trim_end_matches('/'),
+        issue_key
+    );
 
-    for word in words {
-        // If adding this word would exceed the max width, start a new line
-        if !current_line.is_empty() && current_line.len() + 1 + word.len() > max_width {
-            lines.push(current_line.clone());
-            current_line = word.to_string();
-        } else {
-            if !current_line.is_empty() {
-                current_line.push(' ');
-            }
-            current_line.push_str(word);
-        }
-    }
+    let client = Client::new();
+    let mut request = client.get(&url).header("Accept", "application/json");
+    request = match (user, token) {
+        (Some(user), Some(token)) => request.basic_auth(user, Some(token)),
+        (None, Some(token)) => request.bearer_auth(token),
+        _ => request,
+    };
 
-    if !current_line.is_empty() {
-        lines.push(current_line);
+    let response = request
+        .send()
+        .with_context(|| format!("Failed to connect to Jira at {jira_url}"))?;
+
+    if !response.status().is_success() {
+        anyhow::bail!(
+            "Jira API request for {issue_key} failed with status: {}",
+            response.status()
+        );
     }
 
-    lines.join("\n")
+    let json = response
+        .text()
+        .context("Failed to read Jira API response")?;
+    parse_jira_issue_json(&json)
 }
 
-pub fn clean_ai_preamble(text: &str) -> String {
-    let text = text.trim();
+pub fn parse_jira_issue_json(json: &str) -> Result<String> {
+    let parsed: Value =
+        serde_json::from_str(json).context("Failed to parse JSON response from Jira")?;
 
-    // Filter out lines with thinking tags and empty lines
-    let filtered_lines: Vec<&str> = text
-        .lines()
-        .filter(|line| {
-            let trimmed = line.trim();
-            !trimmed.is_empty() && !trimmed.contains("<think>") && !trimmed.contains("</think>")
-        })
-        .collect();
+    let summary = parsed["fields"]["summary"]
+        .as_str()
+        .context("Could not extract issue summary from Jira response")?;
 
-    let mut result = filtered_lines.join("\n");
+    let description = jira_to_markdown(parsed["fields"]["description"].as_str().unwrap_or(""));
+    let description = description.trim();
 
-    // Remove common AI preambles
-    let preambles_to_remove = [
-        "Here's a concise branch description:",
-        "Here is a concise branch description:",
-        "Here's a branch description:",
-        "Here is a branch description:",
-        "Here's the branch description:",
-        "Here is the branch description:",
-        "A concise branch description:",
-        "Branch description:",
-        "Description:",
-        "Summary:",
-        "Here's a summary:",
-        "Here is a summary:",
-        "Based on the content:",
-        "Based on this content:",
-        "Description for this branch:",
-        "A brief description:",
-        "Brief description:",
-    ];
+    let result = if description.is_empty() {
+        summary.to_string()
+    } else {
+        format!("{}\n\n{}", summary, description)
+    };
 
-    for preamble in &preambles_to_remove {
-        if result
-            .trim_start()
-            .to_lowercase()
-            .starts_with(&preamble.to_lowercase())
-        {
-            result = result
-                .trim_start()
-                .strip_prefix(preamble)
-                .unwrap_or(&result)
-                .trim_start()
-                .to_string();
+    Ok(result)
+}
+
+// Jira wiki markup, compiled once for all lines
+static JIRA_HEADING: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^h([1-6])\.\s+(.*)$").unwrap());
+static JIRA_LIST: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^([*#-]+)\s+(.*)$").unwrap());
+static JIRA_CODE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\{(code|noformat)(?::([^}|]*))?[^}]*\}(.*)$").unwrap());
+static JIRA_QUOTE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^bq\.\s+(.*)$").unwrap());
+static JIRA_MONOSPACE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\{\{(.+?)\}\}").unwrap());
+static JIRA_LINK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[([^|\]]+)\|([^\]]+)\]").unwrap());
+static JIRA_BARE_LINK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[((?:https?|mailto):[^\]]+)\]").unwrap());
+static JIRA_BOLD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(^|[^\w*])\*([^*\s](?:[^*]*[^*\s])?)\*").unwrap());
+static JIRA_ITALIC: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(^|[^\w_])_([^_\s](?:[^_]*[^_\s])?)_").unwrap());
+
+/// Converts the common subset of Jira wiki markup to markdown.
+pub fn jira_to_markdown(text: &str) -> String {
+    let mut lines = Vec::new();
+    let mut in_code = false;
+    let mut in_quote = false;
+
+    for line in text.replace("\r\n", "\n").lines() {
+        let trimmed = line.trim();
+
+        if in_code {
+            // Content of code blocks is kept verbatim
+            if let Some(rest) = trimmed
+                .strip_suffix("{code}")
+                .or_else(|| trimmed.strip_suffix("{noformat}"))
+            {
+                if !rest.is_empty() {
+                    lines.push(rest.to_string());
+                }
+                lines.push("```".to_string());
+                in_code = false;
+            } else {
+                lines.push(line.to_string());
+            }
+            continue;
         }
-    }
 
-    // Remove surrounding quotes if present
-    let result = result.trim();
-    if (result.starts_with('"') && result.ends_with('"'))
-        || (result.starts_with('\'') && result.ends_with('\''))
-    {
-        result[1..result.len() - 1].trim().to_string()
-    } else {
-        result.to_string()
-    }
-}
+        if let Some(captures) = JIRA_CODE.captures(trimmed) {
+            let language = captures.get(2).map_or("", |lang| lang.as_str().trim());
+            lines.push(format!("```{language}"));
+            let rest = &captures[3];
+            if let Some(code) = rest
+                .strip_suffix("{code}")
+                .or_else(|| rest.strip_suffix("{noformat}"))
+            {
+                // Single-line code block
+                if !code.is_empty() {
+                    lines.push(code.to_string());
+                }
+                lines.push("```".to_string());
+            } else {
+                if !rest.is_empty() {
+                    lines.push(rest.to_string());
+                }
+                in_code = true;
+            }
+            continue;
+        }
 
-pub fn parse_issue_reference(issue_ref: &str) -> Result<String> {
-    // Check if it's a GitLab issue URL (supports nested groups)
-    let url_regex =
-        Regex::new(r"https?://[^/]+/.+/-/issues/(\d+)").context("Failed to compile URL regex")?;
+        if trimmed == "{quote}" {
+            in_quote = !in_quote;
+            continue;
+        }
+
+        let converted = if let Some(captures) = JIRA_HEADING.captures(trimmed) {
+            let level: usize = captures[1].parse().unwrap_or(1);
+            format!(
+                "{} {}",
+                "#".repeat(level),
+                jira_inline_to_markdown(&captures[2])
+            )
+        } else if let Some(captures) = JIRA_QUOTE.captures(trimmed) {
+            format!("> {}", jira_inline_to_markdown(&captures[1]))
+        } else if let Some(captures) = JIRA_LIST.captures(trimmed) {
+            let marker = &captures[1];
+            let indent = "  ".repeat(marker.len() - 1);
+            let bullet = if marker.ends_with('#') { "1." } else { "-" };
+            format!("{indent}{bullet} {}", jira_inline_to_markdown(&captures[2]))
+        } else if trimmed.starts_with("||") {
+            // Table header row: ||a||b|| becomes | a | b | plus separator
+            let cells: Vec<String> = trimmed
+                .trim_matches('|')
+                .split("||")
+                .map(|cell| jira_inline_to_markdown(cell.trim()))
+                .collect();
+            format!(
+                "| {} |\n|{}",
+                cells.join(" | "),
+                " --- |".repeat(cells.len())
+            )
+        } else if trimmed.starts_with('|') {
+            let cells: Vec<String> = trimmed
+                .trim_matches('|')
+                .split('|')
+                .map(|cell| jira_inline_to_markdown(cell.trim()))
+                .collect();
+            format!("| {} |", cells.join(" | "))
+        } else {
+            jira_inline_to_markdown(line)
+        };
 
-    if let Some(captures) = url_regex.captures(issue_ref) {
-        if let Some(number) = captures.get(1) {
-            return Ok(number.as_str().to_string());
+        if in_quote {
+            lines.push(format!("> {converted}"));
+        } else {
+            lines.push(converted);
         }
     }
 
-    // Check if it's just a number
-    if !issue_ref.is_empty() && issue_ref.chars().all(|c| c.is_ascii_digit()) {
-        return Ok(issue_ref.to_string());
+    if in_code {
+        lines.push("```".to_string());
     }
 
-    anyhow::bail!(
-        "Invalid issue reference: '{}'. Expected issue number or GitLab issue URL.",
-        issue_ref
-    );
+    lines.join("\n")
+}
+
+fn jira_inline_to_markdown(text: &str) -> String {
+    // Bold before italic so the '*' produced for italic is not read as Jira bold
+    let text = JIRA_MONOSPACE.replace_all(text, "`$1`");
+    let text = JIRA_LINK.replace_all(&text, "[$1]($2)");
+    let text = JIRA_BARE_LINK.replace_all(&text, "<$1>");
+    let text = JIRA_BOLD.replace_all(&text, "$1**$2**");
+    let text = JIRA_ITALIC.replace_all(&text, "$1*$2*");
+    text.to_string()
 }
 
 pub fn parse_issue_json(json: &str) -> Result<String> {
@@ -1118,6 +3307,35 @@ mod tests {
         assert_eq!(result, "Implement user authentication feature");
     }
 
+    #[test]
+    fn test_strip_code_fence() {
+        assert_eq!(
+            strip_code_fence("```\nfeat(auth): add login\n\nBody\n```"),
+            "feat(auth): add login\n\nBody"
+        );
+        assert_eq!(
+            strip_code_fence("```markdown\n## Summary\n```\n"),
+            "## Summary"
+        );
+        assert_eq!(strip_code_fence("fix: typo"), "fix: typo");
+    }
+
+    #[test]
+    fn test_validate_for_ai_summarize() {
+        let text = InputSource::CommandLine(Some("Notes".to_string()));
+        assert!(text.validate_for_ai_summarize().is_err());
+        assert!(
+            InputSource::Combined(vec![InputSource::Issue(None), text])
+                .validate_for_ai_summarize()
+                .is_err()
+        );
+        assert!(
+            InputSource::Combined(vec![InputSource::Issue(None), InputSource::Clipboard])
+                .validate_for_ai_summarize()
+                .is_ok()
+        );
+    }
+
     #[test]
     fn test_parse_issue_reference() {
         // Test issue number
@@ -1131,6 +3349,252 @@ mod tests {
         assert!(parse_issue_reference("invalid").is_err());
     }
 
+    #[test]
+    fn test_parse_github_issue_reference() {
+        // Test issue and pull request URLs
+        let url = "https://github.com/owner/repo/issues/123";
+        assert_eq!(
+            parse_github_issue_reference(url).unwrap(),
+            (Some("owner/repo".to_string()), "123".to_string())
+        );
+        let url = "https://github.example.com/team/app/pull/45";
+        assert_eq!(
+            parse_github_issue_reference(url).unwrap(),
+            (Some("team/app".to_string()), "45".to_string())
+        );
+
+        // Test short references
+        assert_eq!(
+            parse_github_issue_reference("#7").unwrap(),
+            (None, "7".to_string())
+        );
+        assert_eq!(
+            parse_github_issue_reference("7").unwrap(),
+            (None, "7".to_string())
+        );
+        assert_eq!(
+            parse_github_issue_reference("owner/repo#8").unwrap(),
+            (Some("owner/repo".to_string()), "8".to_string())
+        );
+
+        // Test invalid input
+        assert!(parse_github_issue_reference("owner/repo8").is_err());
+        assert!(parse_github_issue_reference("https://gitlab.com/owner/repo/-/issues/1").is_err());
+        assert!(parse_github_issue_reference("invalid").is_err());
+    }
+
+    #[test]
+    fn test_parse_github_repo_slug() {
+        assert_eq!(
+            parse_github_repo_slug("https://github.com/owner/repo.git").as_deref(),
+            Some("owner/repo")
+        );
+        assert_eq!(
+            parse_github_repo_slug("git@github.com:owner/repo.git").as_deref(),
+            Some("owner/repo")
+        );
+        assert_eq!(
+            parse_github_repo_slug("ssh://git@github.example.com/team/app").as_deref(),
+            Some("team/app")
+        );
+    }
+
+    #[test]
+    fn test_parse_editor_content() {
+        let edited = format!(
+            "\n## Goal\n\nShip login   \n\n\n# Heading kept\n\n{EDITOR_SCISSORS_LINE}\n# Do not modify\n# main  Something"
+        );
+        assert_eq!(
+            parse_editor_content(&edited),
+            "## Goal\n\nShip login\n\n# Heading kept"
+        );
+
+        // Only the template left means an empty description
+        let untouched = format!("{EDITOR_SCISSORS_LINE}\n# Do not modify\n");
+        assert_eq!(parse_editor_content(&untouched), "");
+    }
+
+    #[test]
+    fn test_split_command_line() {
+        assert_eq!(split_command_line("code --wait"), vec!["code", "--wait"]);
+        assert_eq!(
+            split_command_line(r#""C:\Program Files\Notepad++\notepad++.exe" -multiInst"#),
+            vec![r"C:\Program Files\Notepad++\notepad++.exe", "-multiInst"]
+        );
+        assert_eq!(split_command_line("  vim  "), vec!["vim"]);
+        assert!(split_command_line("").is_empty());
+    }
+
+    #[test]
+    fn test_decode_text() {
+        // UTF-8 with BOM and CRLF line endings
+        assert_eq!(
+            decode_text(b"\xEF\xBB\xBFFix bug\r\nDetails"),
+            "Fix bug\nDetails"
+        );
+
+        // UTF-16 LE and BE with BOM
+        assert_eq!(decode_text(b"\xFF\xFEH\x00i\x00"), "Hi");
+        assert_eq!(decode_text(b"\xFE\xFF\x00H\x00i"), "Hi");
+
+        // UTF-16 LE without BOM
+        assert_eq!(decode_text(b"O\x00K\x00"), "OK");
+
+        // Plain UTF-8 and Windows-1252 fallback
+        assert_eq!(decode_text("Grüße".as_bytes()), "Grüße");
+        assert_eq!(decode_text(b"Gr\xFC\xDFe \x80"), "Grüße €");
+    }
+
+    #[test]
+    fn test_extract_issue_from_branch() {
+        let detect = |branch| extract_issue_from_branch(branch, DEFAULT_ISSUE_PATTERN).unwrap();
+        assert_eq!(detect("bugfix/1234").as_deref(), Some("1234"));
+        assert_eq!(detect("feature/1234-login").as_deref(), Some("1234"));
+        assert_eq!(detect("feature/PROJ-12_login").as_deref(), Some("PROJ-12"));
+        assert_eq!(detect("feature/login-v2"), None);
+        assert_eq!(detect("release/1.2.0"), None);
+        assert_eq!(detect("hotfix/2.3.1"), None);
+        assert_eq!(detect("main"), None);
+
+        // Custom pattern without capture group uses the whole match
+        let custom = extract_issue_from_branch("feature/login-issue42", r"\d+$").unwrap();
+        assert_eq!(custom.as_deref(), Some("42"));
+        assert!(extract_issue_from_branch("main", "(").is_err());
+    }
+
+    #[test]
+    fn test_description_metadata() {
+        let (body, metadata) = split_description_metadata("Plain description");
+        assert_eq!(body, "Plain description");
+        assert!(metadata.is_empty());
+        assert_eq!(
+            join_description_metadata(&body, &metadata),
+            "Plain description"
+        );
+
+        let mut metadata = BTreeMap::new();
+        metadata.insert("issue".to_string(), "#1234".to_string());
+        let stored = join_description_metadata("Fix login\n", &metadata);
+        assert_eq!(stored, "Fix login\n\n<!-- issue: #1234 -->");
+
+        let (body, parsed) = split_description_metadata(&stored);
+        assert_eq!(body, "Fix login");
+        assert_eq!(parsed, metadata);
+    }
+
+    #[test]
+    fn test_parse_merge_request_reference() {
+        assert_eq!(parse_merge_request_reference("12").unwrap(), "12");
+        assert_eq!(parse_merge_request_reference("!12").unwrap(), "12");
+
+        let url = "https://gitlab.com/group/sub/project/-/merge_requests/34";
+        assert_eq!(parse_merge_request_reference(url).unwrap(), "34");
+
+        // Issue URLs are not merge requests and vice versa
+        let issue_url = "https://gitlab.com/group/project/-/issues/5";
+        assert!(parse_merge_request_reference(issue_url).is_err());
+        assert!(parse_issue_reference(url).is_err());
+        assert!(parse_merge_request_reference("!abc").is_err());
+    }
+
+    #[test]
+    fn test_parse_merge_request_json() {
+        let list = r#"[{"iid": 9, "state": "opened", "source_branch": "feature/x"}]"#;
+        assert_eq!(
+            parse_merge_request_list_json(list).unwrap().as_deref(),
+            Some("9")
+        );
+        assert_eq!(parse_merge_request_list_json("[]").unwrap(), None);
+
+        let discussions = r#"[
+            {"notes": [{"body": "added 1 commit", "system": true, "author": {"name": "Bot"}}]},
+            {"notes": [
+                {"body": "Should we cache this?", "system": false, "author": {"name": "Ann"}},
+                {"body": "Yes, per user.", "system": false, "author": {"name": "Bob"}}
+            ]}
+        ]"#;
+        assert_eq!(
+            parse_merge_request_discussions_json(discussions).unwrap(),
+            "- Ann: Should we cache this?\n- Bob: Yes, per user."
+        );
+
+        // Later pages follow the first one
+        let pages = r#"[{"notes": [{"body": "First page", "author": {"name": "Ann"}}]}]
+[{"notes": [{"body": "Second page", "author": {"name": "Bob"}}]}]"#;
+        assert_eq!(
+            parse_merge_request_discussions_json(pages).unwrap(),
+            "- Ann: First page\n- Bob: Second page"
+        );
+        assert!(parse_merge_request_discussions_json(r#"{"message": "404"}"#).is_err());
+    }
+
+    #[test]
+    fn test_jira_references() {
+        assert!(is_jira_key("PROJ-123"));
+        assert!(is_jira_key("AB2_X-1"));
+        assert!(!is_jira_key("proj-123"));
+        assert!(!is_jira_key("123"));
+
+        assert_eq!(
+            find_jira_key("feature/PROJ-123-login").as_deref(),
+            Some("PROJ-123")
+        );
+        assert_eq!(find_jira_key("bugfix/1234"), None);
+
+        assert_eq!(
+            parse_jira_issue_reference("https://jira.example.com/browse/PROJ-7").unwrap(),
+            (
+                Some("https://jira.example.com".to_string()),
+                "PROJ-7".to_string()
+            )
+        );
+        assert_eq!(
+            parse_jira_issue_reference("PROJ-7").unwrap(),
+            (None, "PROJ-7".to_string())
+        );
+        assert!(parse_jira_issue_reference("#7").is_err());
+    }
+
+    #[test]
+    fn test_jira_to_markdown() {
+        assert_eq!(jira_to_markdown("h2. Goal"), "## Goal");
+        assert_eq!(
+            jira_to_markdown("*bold* and _italic_ and {{code}}"),
+            "**bold** and *italic* and `code`"
+        );
+        assert_eq!(
+            jira_to_markdown("keep snake_case_names"),
+            "keep snake_case_names"
+        );
+        assert_eq!(
+            jira_to_markdown("* one\n** nested\n# first"),
+            "- one\n  - nested\n1. first"
+        );
+        assert_eq!(
+            jira_to_markdown("[Docs|https://example.com] and [https://example.com]"),
+            "[Docs](https://example.com) and <https://example.com>"
+        );
+        assert_eq!(
+            jira_to_markdown("{code:rust}\nlet *x* = 1;\n{code}"),
+            "```rust\nlet *x* = 1;\n```"
+        );
+        assert_eq!(
+            jira_to_markdown("||Name||Value||\n|a|b|"),
+            "| Name | Value |\n| --- | --- |\n| a | b |"
+        );
+        assert_eq!(jira_to_markdown("bq. quoted"), "> quoted");
+    }
+
+    #[test]
+    fn test_parse_jira_issue_json() {
+        let json = r#"{"key": "PROJ-1", "fields": {"summary": "Login fails", "description": "h3. Steps\n* open page"}}"#;
+        let result = parse_jira_issue_json(json).unwrap();
+        assert_eq!(result, "Login fails\n\n### Steps\n- open page");
+
+        let json_null = r#"{"fields": {"summary": "Login fails", "description": null}}"#;
+        assert_eq!(parse_jira_issue_json(json_null).unwrap(), "Login fails");
+    }
+
     #[test]
     fn test_parse_issue_json() {
         let json = r#"{"title": "Fix login bug", "description": "The login form is not working properly"}"#;
@@ -1151,6 +3615,18 @@ mod tests {
         assert_eq!(result, "Fix login bug");
     }
 
+    #[test]
+    fn test_parse_github_issue_json() {
+        let json = r#"{"title": "Fix login bug", "body": "The login form is broken", "number": 7}"#;
+        let result = parse_github_issue_json(json).unwrap();
+        assert_eq!(result, "Fix login bug\n\nThe login form is broken");
+
+        // Test with null body
+        let json_null_body = r#"{"title": "Fix login bug", "body": null}"#;
+        let result = parse_github_issue_json(json_null_body).unwrap();
+        assert_eq!(result, "Fix login bug");
+    }
+
     #[test]
     fn test_git_branch_desc_manager_new() {
         let (temp_dir, _repo) = create_test_repo();
@@ -1,6 +1,10 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::{Parser, Subcommand, ValueEnum};
+use git_branch_desc::ai::{AiOverrides, SummarizerKind};
+use git_branch_desc::bulk::{self, BulkOutcome};
 use git_branch_desc::{GitBranchDescManager, InputSource};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 #[derive(Parser)]
 #[command(name = "git-branch-desc")]
@@ -10,7 +14,7 @@ struct Cli {
     command: Commands,
 }
 
-#[derive(Clone, Debug, ValueEnum)]
+#[derive(Clone, Debug, Default, ValueEnum)]
 pub enum InputMethod {
     /// Read from command line argument or interactive prompt (default)
     #[value(name = "cli")]
@@ -21,20 +25,33 @@ pub enum InputMethod {
     /// Read from standard input
     #[value(name = "stdin")]
     Stdin,
+    /// Read from a file given as the positional argument
+    #[value(name = "file")]
+    File,
     /// Read from GitLab issue
     #[value(name = "issue")]
     Issue,
+    /// Read from GitHub issue or pull request
+    #[value(name = "github")]
+    GitHub,
+    /// Read from Jira issue (key detected from branch name when --issue-ref is omitted)
+    #[value(name = "jira")]
+    Jira,
+    /// Read from GitLab merge request (open MR of the branch when --issue-ref is omitted)
+    #[value(name = "mr")]
+    MergeRequest,
+    /// Read commit messages since the branch left its parent branch
+    #[value(name = "commits")]
+    Commits,
+    /// Read the diff against the parent branch
+    #[value(name = "diff")]
+    Diff,
     /// Open external editor with prefilled template
+    #[default]
     #[value(name = "editor")]
     Editor,
 }
 
-impl Default for InputMethod {
-    fn default() -> Self {
-        Self::Editor
-    }
-}
-
 #[derive(Subcommand)]
 enum Commands {
     /// Edit description for a branch (defaults to current branch)
@@ -43,37 +60,71 @@ enum Commands {
         /// Target branch name (defaults to current branch)
         #[arg(short, long)]
         branch: Option<String>,
-        
-        /// Description text (for cli input method only)
+
+        /// Description text (cli input method) or file path (file input method)
         description: Option<String>,
-        
-        /// Input source method
-        #[arg(long, value_enum, default_value = "editor")]
-        input: InputMethod,
-        
-        /// GitLab issue reference (number or URL) - required when input=issue
-        #[arg(long, required_if_eq("input", "issue"))]
-        issue_ref: Option<String>,
-        
+
+        /// Input source method (repeat or comma-separate to combine several sources)
+        #[arg(long, value_enum, value_delimiter = ',', default_value = "editor")]
+        input: Vec<InputMethod>,
+
+        /// Issue or merge request reference (number, key or URL) - required when input=github,
+        /// detected from the branch name when input=issue. Repeat for several issues
+        #[arg(long)]
+        issue_ref: Vec<String>,
+
+        /// Include merge request discussion threads (input=mr with --ai-summarize)
+        #[arg(long, requires = "ai_summarize")]
+        mr_discussions: bool,
+
         /// Use AI to summarize content (works with all input methods except direct cli text)
         #[arg(long)]
         ai_summarize: bool,
-        
+
         /// Timeout in seconds for AI processing (default: 120)
         #[arg(long, default_value = "120")]
         ai_timeout: u64,
-        
+
+        /// Prompt template for --ai-summarize, defined under [prompts.<name>] in .branchdesc.toml
+        #[arg(long, requires = "ai_summarize")]
+        prompt: Option<String>,
+
+        /// How --ai-summarize summarizes (default: auto)
+        #[arg(long, value_enum, requires = "ai_summarize")]
+        summarizer: Option<Summarizer>,
+
+        /// Regenerate the AI summary even if a cached one exists for the same content
+        #[arg(long, requires = "ai_summarize")]
+        no_cache: bool,
+
         /// Automatically commit the BRANCHREADME.md file after editing
         #[arg(short, long)]
         commit: bool,
-        
+
         /// Automatically commit and push the BRANCHREADME.md file after editing
         #[arg(short, long)]
         push: bool,
-        
+
+        /// Overwrite the remote branch if it still points where it was last fetched
+        #[arg(long, requires = "push")]
+        force_with_lease: bool,
+
+        /// Replace the branch's last commit if it is an unpushed description-only commit
+        /// (implies --commit)
+        #[arg(long)]
+        amend: bool,
+
         /// Skip confirmation prompts (force operation)
         #[arg(short, long)]
         force: bool,
+
+        /// Re-commit the description whenever the --input=file file changes (until Ctrl-C)
+        #[arg(short, long)]
+        watch: bool,
+
+        /// Print the description, its diff and the commit that would be created, change nothing
+        #[arg(long, conflicts_with = "watch")]
+        dry_run: bool,
     },
     /// List all local and remote branch descriptions
     #[command(alias = "ls")]
@@ -85,6 +136,185 @@ enum Commands {
         #[arg(short, long)]
         all: bool,
     },
+    /// Browse branches and their descriptions in an interactive terminal UI
+    Tui {
+        /// Timeout in seconds for AI processing (default: 120)
+        #[arg(long, default_value = "120")]
+        ai_timeout: u64,
+    },
+    /// Set the descriptions of many branches from a JSON or CSV file, or in the editor
+    Bulk {
+        /// JSON or CSV file mapping branches to descriptions, '-' for stdin (opens the
+        /// editor with all local branches when omitted)
+        file: Option<PathBuf>,
+        /// Format of the file (default: from the file extension, JSON for stdin)
+        #[arg(long, value_enum)]
+        format: Option<BulkFormat>,
+        /// Push all description commits to origin in one atomic push
+        #[arg(short, long)]
+        push: bool,
+    },
+    /// Manage the cache of AI summaries
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+    /// Generate a merge request description from the branch description, commits and diff
+    #[command(name = "mr-text")]
+    MrText {
+        /// Target branch name (defaults to current branch)
+        #[arg(short, long)]
+        branch: Option<String>,
+        /// Timeout in seconds for AI processing (default: 120)
+        #[arg(long, default_value = "120")]
+        ai_timeout: u64,
+        /// Also copy the generated text to the clipboard
+        #[arg(long)]
+        copy: bool,
+    },
+    /// Generate a conventional commit message for the staged changes or the whole branch
+    #[command(name = "commit-msg")]
+    CommitMsg {
+        /// Target branch name (defaults to current branch)
+        #[arg(short, long)]
+        branch: Option<String>,
+        /// Timeout in seconds for AI processing (default: 120)
+        #[arg(long, default_value = "120")]
+        ai_timeout: u64,
+        /// Also copy the generated message to the clipboard
+        #[arg(long)]
+        copy: bool,
+        /// Commit the staged changes with the message, opening the editor to review it
+        #[arg(short, long, conflicts_with = "branch")]
+        commit: bool,
+    },
+}
+
+#[derive(Clone, Debug, ValueEnum)]
+pub enum Summarizer {
+    /// Use the AI provider, fall back to the built-in summarizer when it cannot be reached
+    #[value(name = "auto")]
+    Auto,
+    /// Use the AI provider only
+    #[value(name = "ai")]
+    Ai,
+    /// Extract title, first paragraph and changed modules without a model
+    #[value(name = "builtin")]
+    Builtin,
+}
+
+impl From<Summarizer> for SummarizerKind {
+    fn from(summarizer: Summarizer) -> Self {
+        match summarizer {
+            Summarizer::Auto => Self::Auto,
+            Summarizer::Ai => Self::Ai,
+            Summarizer::Builtin => Self::Builtin,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, ValueEnum)]
+pub enum BulkFormat {
+    /// `{"branch": "description"}` or `[{"branch": ..., "description": ...}]`
+    #[value(name = "json")]
+    Json,
+    /// `branch,description` rows with an optional header row
+    #[value(name = "csv")]
+    Csv,
+}
+
+#[derive(Subcommand)]
+enum CacheAction {
+    /// Remove cached AI summaries
+    Prune {
+        /// Only remove summaries older than this many days (default: 30)
+        #[arg(long, default_value = "30", conflicts_with = "all")]
+        older_than: u64,
+        /// Remove all cached summaries
+        #[arg(short, long)]
+        all: bool,
+    },
+}
+
+impl InputMethod {
+    fn takes_issue_ref(&self) -> bool {
+        matches!(
+            self,
+            Self::Issue | Self::GitHub | Self::Jira | Self::MergeRequest
+        )
+    }
+}
+
+/// Maps the --input methods and --issue-ref values to an input source. With a single
+/// issue-like input every reference becomes its own section; with several issue-like inputs
+/// the references are assigned to them in order.
+fn build_input_source(
+    inputs: Vec<InputMethod>,
+    description: Option<String>,
+    issue_refs: Vec<String>,
+    mr_discussions: bool,
+) -> Result<InputSource> {
+    // The positional argument is either the text or the path, it cannot serve both
+    if inputs.iter().any(|m| matches!(m, InputMethod::CommandLine))
+        && inputs.iter().any(|m| matches!(m, InputMethod::File))
+    {
+        anyhow::bail!(
+            "--input=cli and --input=file both read the description argument; combine the file with --input=stdin or --input=clipboard instead"
+        );
+    }
+
+    let ref_inputs = inputs.iter().filter(|m| m.takes_issue_ref()).count();
+    if ref_inputs == 0 && !issue_refs.is_empty() {
+        anyhow::bail!("--issue-ref requires --input=issue, github, jira or mr");
+    }
+    if ref_inputs > 1 && !issue_refs.is_empty() && issue_refs.len() != ref_inputs {
+        anyhow::bail!(
+            "Pass one --issue-ref per issue input when combining {ref_inputs} issue inputs"
+        );
+    }
+
+    let mut issue_refs = issue_refs.into_iter();
+    let mut sources = Vec::new();
+    for input in inputs {
+        let refs: Vec<Option<String>> = if !input.takes_issue_ref() {
+            vec![None]
+        } else if ref_inputs == 1 && issue_refs.len() > 0 {
+            issue_refs.by_ref().map(Some).collect()
+        } else {
+            vec![issue_refs.next()]
+        };
+
+        for issue_ref in refs {
+            sources.push(match input {
+                InputMethod::CommandLine => InputSource::CommandLine(description.clone()),
+                InputMethod::Clipboard => InputSource::Clipboard,
+                InputMethod::Stdin => InputSource::Stdin,
+                InputMethod::File => InputSource::File(PathBuf::from(
+                    description
+                        .clone()
+                        .context("--input=file requires a file path")?,
+                )),
+                InputMethod::Issue => InputSource::Issue(issue_ref),
+                InputMethod::GitHub => InputSource::GitHub(
+                    issue_ref.context("--issue-ref is required for --input=github")?,
+                ),
+                InputMethod::Jira => InputSource::Jira(issue_ref),
+                InputMethod::MergeRequest => InputSource::MergeRequest {
+                    reference: issue_ref,
+                    include_discussions: mr_discussions,
+                },
+                InputMethod::Commits => InputSource::Commits,
+                InputMethod::Diff => InputSource::Diff,
+                InputMethod::Editor => InputSource::Editor,
+            });
+        }
+    }
+
+    if sources.len() == 1 {
+        Ok(sources.remove(0))
+    } else {
+        Ok(InputSource::Combined(sources))
+    }
 }
 
 fn main() -> Result<()> {
@@ -97,20 +327,39 @@ fn main() -> Result<()> {
             description,
             input,
             issue_ref,
+            mr_discussions,
             ai_summarize,
             ai_timeout,
+            prompt,
+            summarizer,
+            no_cache,
             commit,
             push,
+            force_with_lease,
+            amend,
             force,
+            watch,
+            dry_run,
         } => {
-            let input_source = match input {
-                InputMethod::CommandLine => InputSource::CommandLine(description),
-                InputMethod::Clipboard => InputSource::Clipboard,
-                InputMethod::Stdin => InputSource::Stdin,
-                InputMethod::Issue => InputSource::Issue(issue_ref.unwrap()),
-                InputMethod::Editor => InputSource::Editor,
-            };
-            
+            let manager = manager
+                .with_force_with_lease(force_with_lease)
+                .with_amend(amend)
+                .with_dry_run(dry_run);
+            if watch {
+                if !matches!(input.as_slice(), [InputMethod::File]) || ai_summarize {
+                    anyhow::bail!("--watch requires --input=file <path> without --ai-summarize");
+                }
+                let path = description.context("--input=file requires a file path")?;
+                return manager.watch_description_file(branch, Path::new(&path), push, force);
+            }
+
+            let input_source = build_input_source(input, description, issue_ref, mr_discussions)?;
+            let manager = manager.with_ai_overrides(AiOverrides {
+                prompt,
+                summarizer: summarizer.map(SummarizerKind::from),
+                no_cache,
+            });
+
             manager.edit_description_v2(
                 branch,
                 input_source,
@@ -122,5 +371,107 @@ fn main() -> Result<()> {
             )
         }
         Commands::List { detailed, all } => manager.list_descriptions(detailed, all),
+        Commands::Tui { ai_timeout } => git_branch_desc::tui::run(&manager, ai_timeout),
+        Commands::Bulk { file, format, push } => {
+            let entries = match file {
+                None => manager.bulk_editor_entries()?,
+                Some(file) => {
+                    let is_stdin = file.as_os_str() == "-";
+                    let format = format.unwrap_or_else(|| {
+                        if !is_stdin && file.extension().is_some_and(|ext| ext == "csv") {
+                            BulkFormat::Csv
+                        } else {
+                            BulkFormat::Json
+                        }
+                    });
+                    let text = if is_stdin {
+                        manager.get_stdin_content()?
+                    } else {
+                        manager.get_file_content(&file)?
+                    };
+                    match format {
+                        BulkFormat::Json => bulk::parse_json(&text)?,
+                        BulkFormat::Csv => bulk::parse_csv(&text)?,
+                    }
+                }
+            };
+            if entries.is_empty() {
+                anyhow::bail!("No branches given");
+            }
+
+            let outcomes = manager.bulk_edit(entries, push)?;
+            let failed = outcomes
+                .iter()
+                .filter(|(_, outcome)| matches!(outcome, BulkOutcome::Failed(_)))
+                .count();
+            if failed > 0 {
+                anyhow::bail!("{failed} of {} branches failed", outcomes.len());
+            }
+            Ok(())
+        }
+        Commands::Cache {
+            action: CacheAction::Prune { older_than, all },
+        } => {
+            let max_age = (!all).then(|| Duration::from_secs(older_than * 24 * 60 * 60));
+            manager.prune_ai_cache(max_age)
+        }
+        Commands::MrText {
+            branch,
+            ai_timeout,
+            copy,
+        } => {
+            let text = manager.generate_mr_text(branch, ai_timeout)?;
+            println!("{text}");
+            if copy {
+                manager.set_clipboard_content(&text)?;
+                eprintln!("✅ Copied to clipboard");
+            }
+            Ok(())
+        }
+        Commands::CommitMsg {
+            branch,
+            ai_timeout,
+            copy,
+            commit,
+        } => {
+            let message = manager.generate_commit_message(branch, ai_timeout)?;
+            if copy {
+                manager.set_clipboard_content(&message)?;
+                eprintln!("✅ Copied to clipboard");
+            }
+            if commit {
+                manager.commit_staged_with_message(&message)
+            } else {
+                println!("{message}");
+                Ok(())
+            }
+        }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_input_source_rejects_cli_with_file() {
+        let err = build_input_source(
+            vec![InputMethod::CommandLine, InputMethod::File],
+            Some("notes.md".to_string()),
+            Vec::new(),
+            false,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("--input=cli and --input=file"));
+
+        assert!(matches!(
+            build_input_source(
+                vec![InputMethod::File, InputMethod::Stdin],
+                Some("notes.md".to_string()),
+                Vec::new(),
+                false,
+            ),
+            Ok(InputSource::Combined(sources)) if sources.len() == 2
+        ));
+    }
+}
@@ -0,0 +1,184 @@
+use crate::ai::render_prompt;
+
+/// Subject of description commits unless `branchdesc.commit.template` is set
+pub const DEFAULT_TEMPLATE: &str = "{action} branch description";
+pub const SKIP_CI_MARKER: &str = "[skip ci]";
+/// Longest `{summary}`, so a subject built from it stays within the usual 72 characters
+const MAX_SUMMARY_CHARS: usize = 50;
+
+/// How description commit messages are built, see `GitBranchDescManager::commit_message_settings`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageSettings {
+    /// Message with `{action}`, `{branch}`, `{issue}` and `{summary}` placeholders
+    pub template: String,
+    /// Append `[skip ci]` to the subject
+    pub skip_ci: bool,
+    /// Add a `Signed-off-by` trailer for the committer
+    pub signoff: bool,
+    /// Trailer lines with the same placeholders, e.g. `Refs: {issue}`
+    pub trailers: Vec<String>,
+}
+
+impl Default for MessageSettings {
+    fn default() -> Self {
+        Self {
+            template: DEFAULT_TEMPLATE.to_string(),
+            skip_ci: true,
+            signoff: false,
+            trailers: Vec::new(),
+        }
+    }
+}
+
+/// Values for the placeholders of a commit message.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MessageVars {
+    /// "Add" or "Update"
+    pub action: String,
+    pub branch: String,
+    /// Linked issues from the description metadata, e.g. "#12, #15"
+    pub issue: Option<String>,
+    /// First line of the description
+    pub summary: String,
+}
+
+impl MessageVars {
+    pub fn new(is_modify: bool, branch: &str, description: &str, issue: Option<String>) -> Self {
+        Self {
+            action: if is_modify { "Update" } else { "Add" }.to_string(),
+            branch: branch.to_string(),
+            issue,
+            summary: summary_line(description),
+        }
+    }
+}
+
+/// Renders the message. Trailers that mention `{issue}` are repeated for every linked issue
+/// and left out when there is none.
+pub fn render(settings: &MessageSettings, vars: &MessageVars, committer: &str) -> String {
+    let issues: Vec<&str> = vars
+        .issue
+        .as_deref()
+        .map(|issue| {
+            issue
+                .split(',')
+                .map(str::trim)
+                .filter(|issue| !issue.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+    let values = |issue: &str| {
+        [
+            ("action", vars.action.clone()),
+            ("branch", vars.branch.clone()),
+            ("issue", issue.to_string()),
+            ("summary", vars.summary.clone()),
+        ]
+    };
+    let render_with = |template: &str, issue: &str| {
+        let values = values(issue);
+        let values: Vec<(&str, &str)> = values
+            .iter()
+            .map(|(name, value)| (*name, value.as_str()))
+            .collect();
+        render_prompt(template, &values)
+    };
+
+    let mut message = render_with(&settings.template, &issues.join(", "))
+        .trim()
+        .to_string();
+    if settings.skip_ci && !message.contains(SKIP_CI_MARKER) && !message.contains("[ci skip]") {
+        let subject_end = message.find('\n').unwrap_or(message.len());
+        message.insert_str(subject_end, &format!(" {SKIP_CI_MARKER}"));
+    }
+
+    let mut trailers = Vec::new();
+    for trailer in &settings.trailers {
+        if trailer.contains("{issue}") {
+            trailers.extend(issues.iter().map(|issue| render_with(trailer, issue)));
+        } else {
+            trailers.push(render_with(trailer, ""));
+        }
+    }
+    if settings.signoff {
+        trailers.push(format!("Signed-off-by: {committer}"));
+    }
+
+    if !trailers.is_empty() {
+        message.push_str("\n\n");
+        message.push_str(&trailers.join("\n"));
+    }
+    message
+}
+
+/// The first text line of a description, shortened at a word to fit a subject line.
+fn summary_line(description: &str) -> String {
+    let line = description
+        .lines()
+        .map(|line| line.trim().trim_start_matches('#').trim())
+        .find(|line| !line.is_empty() && !line.starts_with("<!--"))
+        .unwrap_or_default();
+
+    if line.chars().count() <= MAX_SUMMARY_CHARS {
+        return line.to_string();
+    }
+    let cut: String = line.chars().take(MAX_SUMMARY_CHARS).collect();
+    let cut = cut.rsplit_once(' ').map_or(cut.as_str(), |(head, _)| head);
+    format!("{cut}…")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_message() {
+        let vars = MessageVars::new(false, "feature/x", "Some description", None);
+        assert_eq!(
+            render(&MessageSettings::default(), &vars, "A <a@b.c>"),
+            "Add branch description [skip ci]"
+        );
+    }
+
+    #[test]
+    fn test_message_template_and_trailers() {
+        let settings = MessageSettings {
+            template: "docs({branch}): {summary}\n\nDescribes {issue}".to_string(),
+            skip_ci: true,
+            signoff: true,
+            trailers: vec!["Refs: {issue}".to_string(), "Branch: {branch}".to_string()],
+        };
+        let vars = MessageVars::new(
+            true,
+            "feature/login",
+            "# Login with OAuth\n\nDetails",
+            Some("#12, #15".to_string()),
+        );
+        assert_eq!(
+            render(&settings, &vars, "Test User <test@example.com>"),
+            "docs(feature/login): Login with OAuth [skip ci]\n\nDescribes #12, #15\n\nRefs: #12\nRefs: #15\nBranch: feature/login\nSigned-off-by: Test User <test@example.com>"
+        );
+
+        // Issue trailers are dropped without issues, the marker is optional
+        let settings = MessageSettings {
+            skip_ci: false,
+            signoff: false,
+            ..settings
+        };
+        let vars = MessageVars::new(true, "feature/login", "Login", None);
+        assert_eq!(
+            render(&settings, &vars, ""),
+            "docs(feature/login): Login\n\nDescribes\n\nBranch: feature/login"
+        );
+    }
+
+    #[test]
+    fn test_summary_line() {
+        assert_eq!(summary_line("\n## Title\nBody"), "Title");
+        assert_eq!(
+            summary_line("Implement the new export pipeline for very large reports quickly"),
+            "Implement the new export pipeline for very large…"
+        );
+        assert_eq!(summary_line(""), "");
+    }
+}
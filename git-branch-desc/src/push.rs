@@ -0,0 +1,393 @@
+use anyhow::{Context, Result};
+use git2::{
+    Config, Cred, CredentialType, Direction, ErrorCode, Oid, PushOptions, RemoteCallbacks,
+    Repository,
+};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fmt;
+use std::process::Command;
+
+/// Why a push failed, for callers that react differently to each case. Returned wrapped in
+/// `anyhow::Error`; use `downcast_ref::<PushError>()` to inspect it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PushError {
+    /// The remote branch has commits the local branch does not have
+    NonFastForward { branch: String },
+    /// `--force-with-lease` refused because the remote branch moved since the last fetch
+    StaleLease {
+        branch: String,
+        expected: Option<Oid>,
+        actual: Option<Oid>,
+    },
+    /// Neither the SSH agent, a credential helper nor default credentials were accepted
+    Authentication { remote: String, message: String },
+    /// A server-side hook (pre-receive, update) declined the update
+    HookRejected { branch: String, message: String },
+    /// Any other rejection reported by the remote
+    Rejected { branch: String, message: String },
+}
+
+impl fmt::Display for PushError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NonFastForward { branch } => write!(
+                f,
+                "Push of '{branch}' was rejected because the remote branch has commits that are not in the local branch. Pull first, or use --force-with-lease to overwrite them."
+            ),
+            Self::StaleLease {
+                branch,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Remote branch '{branch}' is at {} but was at {} when last fetched. Not overwriting it; fetch and review the new commits first.",
+                short_oid(*actual),
+                short_oid(*expected)
+            ),
+            Self::Authentication { remote, message } => write!(
+                f,
+                "Authentication to remote '{remote}' failed: {message}. Check your credential helper or SSH agent."
+            ),
+            Self::HookRejected { branch, message } => {
+                write!(
+                    f,
+                    "A server hook rejected the push of '{branch}': {message}"
+                )
+            }
+            Self::Rejected { branch, message } => {
+                write!(f, "Remote rejected the push of '{branch}': {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PushError {}
+
+fn short_oid(oid: Option<Oid>) -> String {
+    match oid {
+        Some(oid) => oid.to_string()[..7].to_string(),
+        None => "(none)".to_string(),
+    }
+}
+
+/// Maps the status message the remote sent for a rejected reference to an error.
+pub fn classify_rejection(branch: &str, message: &str) -> PushError {
+    let lower = message.to_lowercase();
+    let branch = branch.to_string();
+    let message = message.to_string();
+
+    if lower.contains("non-fast-forward") || lower.contains("fetch first") {
+        PushError::NonFastForward { branch }
+    } else if lower.contains("hook declined") || lower.contains("hook rejected") {
+        PushError::HookRejected { branch, message }
+    } else {
+        PushError::Rejected { branch, message }
+    }
+}
+
+/// Credential methods already offered to the remote. libgit2 asks again after each rejected
+/// credential, so every method is tried once before giving up.
+#[derive(Default)]
+struct CredentialAttempts {
+    ssh_agent: Cell<bool>,
+    username: Cell<bool>,
+    helper: Cell<bool>,
+    default: Cell<bool>,
+    exhausted: Cell<bool>,
+}
+
+fn remote_callbacks<'a>(
+    config: &'a Config,
+    attempts: &'a CredentialAttempts,
+) -> RemoteCallbacks<'a> {
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(move |url, username_from_url, allowed| {
+        let username = username_from_url.unwrap_or("git");
+
+        if allowed.contains(CredentialType::USERNAME) && !attempts.username.replace(true) {
+            return Cred::username(username);
+        }
+        if allowed.contains(CredentialType::SSH_KEY) && !attempts.ssh_agent.replace(true) {
+            return Cred::ssh_key_from_agent(username);
+        }
+        if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) && !attempts.helper.replace(true) {
+            return Cred::credential_helper(config, url, username_from_url);
+        }
+        if allowed.contains(CredentialType::DEFAULT) && !attempts.default.replace(true) {
+            return Cred::default();
+        }
+
+        attempts.exhausted.set(true);
+        Err(git2::Error::from_str(
+            "no credentials accepted (tried SSH agent and credential helpers)",
+        ))
+    });
+    callbacks
+}
+
+/// Pushes `refs/heads/<branch>` to the same branch on `remote_name`. With `force_with_lease`
+/// the remote branch is overwritten only while it still points where our remote-tracking
+/// branch says it does, like `git push --force-with-lease`.
+pub fn push_branch(
+    repo: &Repository,
+    remote_name: &str,
+    branch: &str,
+    force_with_lease: bool,
+) -> Result<()> {
+    push_ref(
+        repo,
+        remote_name,
+        &format!("refs/heads/{branch}"),
+        branch,
+        force_with_lease,
+    )
+}
+
+/// Pushes a commit that no local branch points to, e.g. one created on top of a
+/// remote-tracking branch, to `refs/heads/<branch>` on the remote.
+pub fn push_commit(
+    repo: &Repository,
+    remote_name: &str,
+    commit_id: Oid,
+    branch: &str,
+    force_with_lease: bool,
+) -> Result<()> {
+    // libgit2 pushes references only, so the commit gets a short-lived one outside refs/heads
+    let mut temp_ref = repo.reference(TEMP_PUSH_REF, commit_id, true, "branchdesc: push")?;
+    let result = push_ref(repo, remote_name, TEMP_PUSH_REF, branch, force_with_lease);
+    temp_ref.delete()?;
+    result
+}
+
+/// Reference `push_commit` pushes from
+const TEMP_PUSH_REF: &str = "refs/branchdesc/push";
+
+fn push_ref(
+    repo: &Repository,
+    remote_name: &str,
+    local_ref: &str,
+    branch: &str,
+    force_with_lease: bool,
+) -> Result<()> {
+    let mut remote = repo
+        .find_remote(remote_name)
+        .with_context(|| format!("Remote '{remote_name}' not found"))?;
+    let config = repo.config()?;
+    let remote_ref = format!("refs/heads/{branch}");
+    let mut refspec = format!("{local_ref}:{remote_ref}");
+
+    if force_with_lease {
+        let expected = repo
+            .refname_to_id(&format!("refs/remotes/{remote_name}/{branch}"))
+            .ok();
+        let actual = list_remote_heads(&mut remote, &config, remote_name, branch)?
+            .get(&remote_ref)
+            .copied();
+
+        if expected != actual {
+            return Err(PushError::StaleLease {
+                branch: branch.to_string(),
+                expected,
+                actual,
+            }
+            .into());
+        }
+        refspec.insert(0, '+');
+    }
+
+    let attempts = CredentialAttempts::default();
+    let rejection = RefCell::new(None);
+    let mut callbacks = remote_callbacks(&config, &attempts);
+    callbacks.push_update_reference(|_refname, status| {
+        if let Some(message) = status {
+            *rejection.borrow_mut() = Some(message.to_string());
+        }
+        Ok(())
+    });
+    let mut options = PushOptions::new();
+    options.remote_callbacks(callbacks);
+
+    remote
+        .push(&[refspec.as_str()], Some(&mut options))
+        .map_err(|err| classify_error(err, remote_name, branch, &attempts))?;
+
+    if let Some(message) = rejection.borrow_mut().take() {
+        return Err(classify_rejection(branch, &message).into());
+    }
+
+    // Like git, remember where the remote branch is now, for leases and `--amend`
+    repo.reference(
+        &format!("refs/remotes/{remote_name}/{branch}"),
+        repo.refname_to_id(local_ref)?,
+        true,
+        "branchdesc: push",
+    )?;
+    Ok(())
+}
+
+/// One branch of a multi-branch push: `local_ref` is pushed to `refs/heads/<branch>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefUpdate {
+    pub local_ref: String,
+    pub branch: String,
+    pub commit: Oid,
+}
+
+/// Pushes all updates with `git push --atomic`, so the remote updates either every branch or
+/// none, and returns the result per branch. libgit2 cannot request an atomic push, so this
+/// runs git itself, which also brings git's own credential handling.
+pub fn push_atomic(
+    repo: &Repository,
+    remote_name: &str,
+    updates: &[RefUpdate],
+) -> Result<Vec<(String, std::result::Result<(), PushError>)>> {
+    let refspecs: Vec<String> = updates
+        .iter()
+        .map(|update| format!("{}:refs/heads/{}", update.local_ref, update.branch))
+        .collect();
+    let output = Command::new("git")
+        .arg("--git-dir")
+        .arg(repo.path())
+        .args(["push", "--atomic", "--porcelain", remote_name])
+        .args(&refspecs)
+        .output()
+        .context("Failed to execute git push")?;
+    let statuses = parse_porcelain_push(&String::from_utf8_lossy(&output.stdout));
+
+    let mut results = Vec::new();
+    for update in updates {
+        let result = match statuses.get(&format!("refs/heads/{}", update.branch)) {
+            Some(None) => Ok(()),
+            Some(Some(reason)) if reason == "atomic push failed" => Err(PushError::Rejected {
+                branch: update.branch.clone(),
+                message: "not pushed, another branch of the atomic push was rejected".to_string(),
+            }),
+            Some(Some(reason)) => Err(classify_rejection(&update.branch, reason)),
+            // Without a status per branch git did not get as far as pushing; its first
+            // line says why, the rest are hints
+            None => anyhow::bail!(
+                "Push to remote '{remote_name}' failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+                    .lines()
+                    .find(|line| !line.trim().is_empty())
+                    .unwrap_or("git push exited without output")
+            ),
+        };
+        results.push((update.branch.clone(), result));
+    }
+    Ok(results)
+}
+
+/// The status lines of `git push --porcelain`, by remote reference: `None` when it was
+/// updated, otherwise the reason it was rejected.
+fn parse_porcelain_push(output: &str) -> HashMap<String, Option<String>> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            let flag = fields.next()?;
+            let (_, to) = fields.next()?.split_once(':')?;
+            let summary = fields.next().unwrap_or_default();
+            let rejection = (flag == "!").then(|| {
+                summary
+                    .rsplit_once(" (")
+                    .and_then(|(_, reason)| reason.strip_suffix(')'))
+                    .unwrap_or(summary)
+                    .to_string()
+            });
+            Some((to.to_string(), rejection))
+        })
+        .collect()
+}
+
+/// The branches and tags on the remote with the commits they point to.
+fn list_remote_heads(
+    remote: &mut git2::Remote,
+    config: &Config,
+    remote_name: &str,
+    branch: &str,
+) -> Result<HashMap<String, Oid>> {
+    let attempts = CredentialAttempts::default();
+    let connection = remote
+        .connect_auth(
+            Direction::Push,
+            Some(remote_callbacks(config, &attempts)),
+            None,
+        )
+        .map_err(|err| classify_error(err, remote_name, branch, &attempts))?;
+    Ok(connection
+        .list()?
+        .iter()
+        .map(|head| (head.name().to_string(), head.oid()))
+        .collect())
+}
+
+fn classify_error(
+    err: git2::Error,
+    remote_name: &str,
+    branch: &str,
+    attempts: &CredentialAttempts,
+) -> anyhow::Error {
+    if err.code() == ErrorCode::NotFastForward {
+        return PushError::NonFastForward {
+            branch: branch.to_string(),
+        }
+        .into();
+    }
+    if err.code() == ErrorCode::Auth || attempts.exhausted.get() {
+        return PushError::Authentication {
+            remote: remote_name.to_string(),
+            message: err.message().to_string(),
+        }
+        .into();
+    }
+    anyhow::Error::new(err).context(format!(
+        "Failed to push branch '{branch}' to '{remote_name}'"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_porcelain_push() {
+        let output = "To ../origin.git\n\
+                      \x20\trefs/heads/main:refs/heads/main\tae80934..54ba181\n\
+                      *\trefs/branchdesc/bulk/new:refs/heads/new\t[new branch]\n\
+                      !\trefs/heads/b:refs/heads/b\t[rejected] (non-fast-forward)\n\
+                      !\trefs/heads/c:refs/heads/c\t[remote rejected] (pre-receive hook declined)\n\
+                      Done\n";
+        let statuses = parse_porcelain_push(output);
+        assert_eq!(statuses.len(), 4);
+        assert_eq!(statuses["refs/heads/main"], None);
+        assert_eq!(statuses["refs/heads/new"], None);
+        assert_eq!(
+            statuses["refs/heads/b"].as_deref(),
+            Some("non-fast-forward")
+        );
+        assert_eq!(
+            statuses["refs/heads/c"].as_deref(),
+            Some("pre-receive hook declined")
+        );
+    }
+
+    #[test]
+    fn test_classify_rejection() {
+        assert_eq!(
+            classify_rejection("feature/x", "non-fast-forward"),
+            PushError::NonFastForward {
+                branch: "feature/x".to_string()
+            }
+        );
+        assert!(matches!(
+            classify_rejection("feature/x", "pre-receive hook declined"),
+            PushError::HookRejected { .. }
+        ));
+        assert!(matches!(
+            classify_rejection("feature/x", "protected branch"),
+            PushError::Rejected { .. }
+        ));
+    }
+}
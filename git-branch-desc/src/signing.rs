@@ -0,0 +1,180 @@
+use anyhow::{Context, Result};
+use git2::{Commit, Config, Oid, Repository, Signature, Tree};
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// The signature formats of `gpg.format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SigningFormat {
+    OpenPgp,
+    X509,
+    Ssh,
+}
+
+impl SigningFormat {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "openpgp" => Ok(Self::OpenPgp),
+            "x509" => Ok(Self::X509),
+            "ssh" => Ok(Self::Ssh),
+            other => {
+                anyhow::bail!("Unsupported gpg.format '{other}'. Use 'openpgp', 'x509' or 'ssh'.")
+            }
+        }
+    }
+}
+
+/// Creates a commit without updating any reference. Like `git commit`, it is signed when
+/// `commit.gpgsign` is set, using `gpg.format` and `user.signingkey`.
+pub fn create_commit(
+    repo: &Repository,
+    signature: &Signature,
+    message: &str,
+    tree: &Tree,
+    parents: &[&Commit],
+) -> Result<Oid> {
+    let config = repo.config()?;
+    if !config.get_bool("commit.gpgsign").unwrap_or(false) {
+        return repo
+            .commit(None, signature, signature, message, tree, parents)
+            .context("Failed to create commit");
+    }
+
+    let buffer = repo
+        .commit_create_buffer(signature, signature, message, tree, parents)
+        .context("Failed to create commit buffer")?;
+    let buffer = buffer
+        .as_str()
+        .context("Commit buffer is not valid UTF-8")?;
+    let commit_signature = sign_buffer(&config, signature, buffer)?;
+
+    repo.commit_signed(buffer, commit_signature.trim_end(), None)
+        .context("Failed to create signed commit")
+}
+
+/// Signs a commit buffer the way git does and returns the detached, armored signature.
+pub fn sign_buffer(config: &Config, signature: &Signature, buffer: &str) -> Result<String> {
+    let format = match config.get_string("gpg.format") {
+        Ok(name) => SigningFormat::parse(&name)?,
+        Err(_) => SigningFormat::OpenPgp,
+    };
+    let signing_key = config.get_string("user.signingkey").ok();
+
+    match format {
+        SigningFormat::OpenPgp | SigningFormat::X509 => {
+            let program = if format == SigningFormat::OpenPgp {
+                config
+                    .get_string("gpg.openpgp.program")
+                    .or_else(|_| config.get_string("gpg.program"))
+                    .unwrap_or_else(|_| "gpg".to_string())
+            } else {
+                config
+                    .get_string("gpg.x509.program")
+                    .unwrap_or_else(|_| "gpgsm".to_string())
+            };
+            // Without a signing key git signs with the committer identity
+            let key = signing_key.unwrap_or_else(|| {
+                format!(
+                    "{} <{}>",
+                    signature.name().unwrap_or_default(),
+                    signature.email().unwrap_or_default()
+                )
+            });
+            run_signer(
+                Command::new(&program).args(["--status-fd=2", "-bsau", &key]),
+                &program,
+                buffer,
+            )
+        }
+        SigningFormat::Ssh => {
+            let program = config
+                .get_string("gpg.ssh.program")
+                .unwrap_or_else(|_| "ssh-keygen".to_string());
+            let key = signing_key.context("gpg.format is 'ssh' but user.signingkey is not set")?;
+
+            let mut command = Command::new(&program);
+            command.args(["-Y", "sign", "-n", "git", "-f"]);
+
+            // A literal public key is signed with the matching key in the SSH agent
+            let literal_key = key.strip_prefix("key::").unwrap_or(&key);
+            if literal_key.starts_with("ssh-") || literal_key.starts_with("ecdsa-") {
+                let key_file = std::env::temp_dir().join(format!(
+                    "git-branch-desc-signing-{}.pub",
+                    std::process::id()
+                ));
+                fs::write(&key_file, format!("{literal_key}\n"))
+                    .context("Failed to write SSH signing key file")?;
+                command.arg(&key_file).arg("-U");
+                let result = run_signer(&mut command, &program, buffer);
+                let _ = fs::remove_file(&key_file);
+                result
+            } else {
+                command.arg(expand_home(&key));
+                run_signer(&mut command, &program, buffer)
+            }
+        }
+    }
+}
+
+/// Feeds the buffer on stdin and returns stdout, the signature.
+fn run_signer(command: &mut Command, program: &str, buffer: &str) -> Result<String> {
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run '{program}' to sign the commit"))?;
+
+    // A signer that fails early closes stdin; its stderr explains more than the broken pipe
+    let written = child
+        .stdin
+        .take()
+        .context("Failed to open signing program stdin")?
+        .write_all(buffer.as_bytes());
+
+    let output = child.wait_with_output()?;
+    let commit_signature = String::from_utf8_lossy(&output.stdout).into_owned();
+    if !output.status.success() || commit_signature.trim().is_empty() {
+        anyhow::bail!(
+            "Failed to sign the commit with '{program}': {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    written.context("Failed to pass the commit to the signing program")?;
+    Ok(commit_signature)
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~/").zip(dirs::home_dir()) {
+        Some((rest, home)) => home.join(rest),
+        None => PathBuf::from(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signing_format_parse() {
+        assert_eq!(
+            SigningFormat::parse("openpgp").unwrap(),
+            SigningFormat::OpenPgp
+        );
+        assert_eq!(SigningFormat::parse("SSH").unwrap(), SigningFormat::Ssh);
+        assert!(SigningFormat::parse("pgp").is_err());
+    }
+
+    #[test]
+    fn test_expand_home() {
+        assert_eq!(
+            expand_home("/keys/id_ed25519"),
+            PathBuf::from("/keys/id_ed25519")
+        );
+        if let Some(home) = dirs::home_dir() {
+            assert_eq!(expand_home("~/.ssh/id"), home.join(".ssh/id"));
+        }
+    }
+}
@@ -0,0 +1,595 @@
+use crate::{BranchDescription, GitBranchDescManager, InputSource};
+use anyhow::{Context, Result};
+use ratatui::DefaultTerminal;
+use ratatui::Frame;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::crossterm::{execute, terminal};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+use std::io::{self, IsTerminal};
+
+const KEY_HELP: &str =
+    "/ filter  e edit  i issue  a AI  c commit  p push  o checkout  r reload  q quit";
+
+/// Branch browser: branch list on the left, rendered description on the right. Actions
+/// leave the alternate screen and run the regular `edit`/`list` code paths.
+pub fn run(manager: &GitBranchDescManager, ai_timeout: u64) -> Result<()> {
+    if !io::stdout().is_terminal() {
+        anyhow::bail!("The TUI requires an interactive terminal");
+    }
+
+    let mut app = App::new(ai_timeout);
+    app.reload(manager)?;
+
+    let mut terminal = ratatui::try_init().context("Failed to initialize terminal")?;
+    let result = app.event_loop(&mut terminal, manager);
+    ratatui::restore();
+    result
+}
+
+#[derive(Clone, Copy)]
+enum Action {
+    Edit,
+    ImportIssue,
+    AiSummarize,
+    Commit,
+    Push,
+    Checkout,
+}
+
+impl Action {
+    fn label(self) -> &'static str {
+        match self {
+            Action::Edit => "Editing",
+            Action::ImportIssue => "Importing issue into",
+            Action::AiSummarize => "Summarizing commits and changes of",
+            Action::Commit => "Committing description of",
+            Action::Push => "Pushing",
+            Action::Checkout => "Checking out",
+        }
+    }
+}
+
+struct App {
+    ai_timeout: u64,
+    branches: Vec<BranchDescription>,
+    current_branch: String,
+    /// Listed name of the current branch, "origin/<branch>" once it is pushed
+    current_entry: Option<String>,
+    uncommitted: bool,
+    filter: String,
+    filtering: bool,
+    visible: Vec<usize>,
+    list_state: ListState,
+    scroll: u16,
+    status: String,
+}
+
+impl App {
+    fn new(ai_timeout: u64) -> Self {
+        Self {
+            ai_timeout,
+            branches: Vec::new(),
+            current_branch: String::new(),
+            current_entry: None,
+            uncommitted: false,
+            filter: String::new(),
+            filtering: false,
+            visible: Vec::new(),
+            list_state: ListState::default(),
+            scroll: 0,
+            status: String::new(),
+        }
+    }
+
+    fn reload(&mut self, manager: &GitBranchDescManager) -> Result<()> {
+        let selected = self.selected().map(|b| b.branch.clone());
+
+        self.branches = manager.collect_descriptions(true)?;
+        self.current_branch = manager.get_current_branch()?;
+        self.current_entry = None;
+        for desc in &self.branches {
+            if manager.branch_name_from_listing(&desc.branch)? == self.current_branch {
+                self.current_entry = Some(desc.branch.clone());
+            }
+        }
+
+        // Show the working copy of the current branch, edits there are committed with 'c'
+        let working_copy = manager.read_current_branch_description()?;
+        let (working_copy, _) = crate::split_description_metadata(&working_copy);
+        let committed = manager
+            .read_branch_description_from_git(&self.current_branch)?
+            .map(|d| crate::split_description_metadata(&d).0)
+            .unwrap_or_default();
+        self.uncommitted = working_copy.trim() != committed.trim();
+        if self.uncommitted {
+            for desc in &mut self.branches {
+                if self.current_entry.as_ref() == Some(&desc.branch) {
+                    desc.description = working_copy.trim().to_string();
+                }
+            }
+        }
+
+        self.apply_filter();
+        if let Some(selected) = selected
+            && let Some(pos) = self
+                .visible
+                .iter()
+                .position(|&i| self.branches[i].branch == selected)
+        {
+            self.list_state.select(Some(pos));
+        }
+        Ok(())
+    }
+
+    fn apply_filter(&mut self) {
+        let mut scored: Vec<(i64, usize)> = self
+            .branches
+            .iter()
+            .enumerate()
+            .filter_map(|(i, desc)| {
+                let first_line = desc.description.lines().next().unwrap_or("");
+                fuzzy_score(&self.filter, &desc.branch)
+                    .or_else(|| fuzzy_score(&self.filter, first_line).map(|s| s / 2))
+                    .map(|score| (score, i))
+            })
+            .collect();
+        scored.sort_by_key(|&(score, i)| (-score, i));
+        self.visible = scored.into_iter().map(|(_, i)| i).collect();
+
+        self.list_state.select(if self.visible.is_empty() {
+            None
+        } else {
+            Some(0)
+        });
+        self.scroll = 0;
+    }
+
+    fn selected(&self) -> Option<&BranchDescription> {
+        self.list_state
+            .selected()
+            .and_then(|pos| self.visible.get(pos))
+            .map(|&i| &self.branches[i])
+    }
+
+    fn event_loop(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        manager: &GitBranchDescManager,
+    ) -> Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            if self.filtering {
+                self.handle_filter_key(key);
+                continue;
+            }
+
+            let action = match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    return Ok(());
+                }
+                KeyCode::Char('/') => {
+                    self.filtering = true;
+                    None
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    self.move_selection(1);
+                    None
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.move_selection(-1);
+                    None
+                }
+                KeyCode::PageDown | KeyCode::Char('J') => {
+                    self.scroll = self.scroll.saturating_add(5);
+                    None
+                }
+                KeyCode::PageUp | KeyCode::Char('K') => {
+                    self.scroll = self.scroll.saturating_sub(5);
+                    None
+                }
+                KeyCode::Char('r') => {
+                    self.reload(manager)?;
+                    self.status = "Reloaded".to_string();
+                    None
+                }
+                KeyCode::Char('e') => Some(Action::Edit),
+                KeyCode::Char('i') => Some(Action::ImportIssue),
+                KeyCode::Char('a') => Some(Action::AiSummarize),
+                KeyCode::Char('c') => Some(Action::Commit),
+                KeyCode::Char('p') => Some(Action::Push),
+                KeyCode::Char('o') | KeyCode::Enter => Some(Action::Checkout),
+                _ => None,
+            };
+
+            if let Some(action) = action {
+                self.run_action(terminal, manager, action)?;
+            }
+        }
+    }
+
+    fn handle_filter_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Enter => self.filtering = false,
+            KeyCode::Esc => {
+                self.filtering = false;
+                self.filter.clear();
+                self.apply_filter();
+            }
+            KeyCode::Backspace => {
+                self.filter.pop();
+                self.apply_filter();
+            }
+            KeyCode::Down => self.move_selection(1),
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Char(c) => {
+                self.filter.push(c);
+                self.apply_filter();
+            }
+            _ => {}
+        }
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        if self.visible.is_empty() {
+            return;
+        }
+        let current = self.list_state.selected().unwrap_or(0) as isize;
+        let next = (current + delta).clamp(0, self.visible.len() as isize - 1);
+        self.list_state.select(Some(next as usize));
+        self.scroll = 0;
+    }
+
+    /// Runs an action outside the alternate screen so editors, prompts and progress output
+    /// behave exactly like on the command line.
+    fn run_action(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        manager: &GitBranchDescManager,
+        action: Action,
+    ) -> Result<()> {
+        let Some(selected) = self.selected() else {
+            return Ok(());
+        };
+        let branch = manager.branch_name_from_listing(&selected.branch)?;
+
+        if matches!(action, Action::Commit)
+            && let Some(reason) = self.commit_refusal(&branch)
+        {
+            self.status = reason.to_string();
+            return Ok(());
+        }
+
+        suspend()?;
+        println!("{} '{branch}'...", action.label());
+        let result = self.perform(manager, action, &branch);
+        match &result {
+            Ok(()) => self.status = format!("{} '{branch}' done", action.label()),
+            Err(e) => {
+                println!("❌ {e:#}");
+                self.status = format!("{} '{branch}' failed", action.label());
+            }
+        }
+        println!("\nPress Enter to return");
+        io::stdin().read_line(&mut String::new())?;
+        resume(terminal)?;
+
+        self.reload(manager)
+    }
+
+    /// Why `c` cannot commit the selected branch. Only the working copy of the current
+    /// branch is committed; it must never end up on HEAD under another branch's name.
+    fn commit_refusal(&self, branch: &str) -> Option<&'static str> {
+        if branch != self.current_branch {
+            Some("Descriptions of other branches are committed when edited")
+        } else if !self.uncommitted {
+            Some("Nothing to commit")
+        } else {
+            None
+        }
+    }
+
+    fn perform(&self, manager: &GitBranchDescManager, action: Action, branch: &str) -> Result<()> {
+        // Edits of other branches are confirmed first; for a remote-only branch the
+        // confirmation says that the commit is pushed, as there is nowhere else to keep it
+        let edit = |input_source, ai_summarize| {
+            manager.edit_description_v2(
+                Some(branch.to_string()),
+                input_source,
+                ai_summarize,
+                self.ai_timeout,
+                false,
+                manager.is_remote_only_branch(branch),
+                false,
+            )
+        };
+
+        match action {
+            Action::Edit => edit(InputSource::Editor, false),
+            Action::ImportIssue => edit(InputSource::Issue(None), false),
+            Action::AiSummarize => edit(
+                InputSource::Combined(vec![InputSource::Commits, InputSource::Diff]),
+                true,
+            ),
+            Action::Commit => {
+                let is_modify = manager
+                    .read_branch_description_from_git(branch)?
+                    .is_some_and(|d| !d.trim().is_empty());
+                manager.commit_current_branch_changes(branch, is_modify, false)
+            }
+            Action::Push => manager.push_branch(branch),
+            Action::Checkout => manager.checkout_branch(branch),
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, footer] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let [left, right] =
+            Layout::horizontal([Constraint::Percentage(35), Constraint::Percentage(65)])
+                .areas(main);
+
+        let items: Vec<ListItem> = self
+            .visible
+            .iter()
+            .map(|&i| {
+                let desc = &self.branches[i];
+                let is_current = self.current_entry.as_ref() == Some(&desc.branch);
+                let marker = if is_current { "* " } else { "  " };
+                let style = if desc.description == "(no description)" {
+                    Style::default().fg(Color::DarkGray)
+                } else {
+                    Style::default()
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(marker, Style::default().fg(Color::Green)),
+                    Span::styled(desc.branch.clone(), style),
+                ]))
+            })
+            .collect();
+
+        let title = if self.filter.is_empty() && !self.filtering {
+            format!(" Branches ({}) ", self.visible.len())
+        } else {
+            format!(" Branches ({}) /{} ", self.visible.len(), self.filter)
+        };
+        let list = List::new(items)
+            .block(Block::bordered().title(title))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, left, &mut self.list_state);
+
+        let (title, text) = match self.selected() {
+            Some(desc) => {
+                let mut title = format!(" {} ", desc.branch);
+                if self.uncommitted && self.current_entry.as_ref() == Some(&desc.branch) {
+                    title.push_str("(uncommitted) ");
+                }
+                (title, render_markdown(&desc.description))
+            }
+            None => (
+                " Description ".to_string(),
+                Text::from("No matching branches"),
+            ),
+        };
+        let description = Paragraph::new(text)
+            .block(Block::bordered().title(title))
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0));
+        frame.render_widget(description, right);
+
+        let footer_text = if self.filtering {
+            "Type to filter, Enter to keep, Esc to clear".to_string()
+        } else if self.status.is_empty() {
+            KEY_HELP.to_string()
+        } else {
+            format!("{}  |  {KEY_HELP}", self.status)
+        };
+        frame.render_widget(Line::from(footer_text).dark_gray(), footer);
+    }
+}
+
+fn suspend() -> Result<()> {
+    terminal::disable_raw_mode()?;
+    execute!(io::stdout(), terminal::LeaveAlternateScreen)?;
+    Ok(())
+}
+
+fn resume(terminal: &mut DefaultTerminal) -> Result<()> {
+    execute!(io::stdout(), terminal::EnterAlternateScreen)?;
+    terminal::enable_raw_mode()?;
+    terminal.clear()?;
+    Ok(())
+}
+
+/// Scores a case-insensitive subsequence match of `pattern` in `candidate`, favouring
+/// consecutive characters and matches at word starts. Returns `None` when not all
+/// characters of the pattern occur in order.
+pub fn fuzzy_score(pattern: &str, candidate: &str) -> Option<i64> {
+    let mut pattern_chars = pattern.chars().flat_map(char::to_lowercase).peekable();
+    let mut score = 0;
+    let mut previous: Option<char> = None;
+    let mut previous_matched = false;
+
+    for c in candidate.chars() {
+        let Some(&wanted) = pattern_chars.peek() else {
+            break;
+        };
+        let matched = c.to_lowercase().eq(std::iter::once(wanted));
+        if matched {
+            score += 1;
+            if previous_matched {
+                score += 5;
+            }
+            if previous.is_none_or(|p| matches!(p, '/' | '-' | '_' | '.' | ' ')) {
+                score += 3;
+            }
+            pattern_chars.next();
+        }
+        previous_matched = matched;
+        previous = Some(c);
+    }
+
+    pattern_chars.peek().is_none().then_some(score)
+}
+
+/// Renders the Markdown subset used in branch descriptions: headings, lists, quotes, fenced
+/// code blocks and inline `code`/**bold** spans.
+pub fn render_markdown(markdown: &str) -> Text<'static> {
+    let mut lines = Vec::new();
+    let mut in_code_block = false;
+
+    for line in markdown.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            lines.push(Line::from(Span::styled(
+                format!("  {line}"),
+                Style::default().fg(Color::Yellow),
+            )));
+            continue;
+        }
+
+        let trimmed = line.trim_start();
+        let indent = &line[..line.len() - trimmed.len()];
+        if let Some(heading) = trimmed
+            .strip_prefix('#')
+            .map(|h| h.trim_start_matches('#'))
+            .filter(|h| h.is_empty() || h.starts_with(' '))
+        {
+            lines.push(Line::from(Span::styled(
+                heading.trim().to_string(),
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )));
+        } else if let Some(item) = trimmed
+            .strip_prefix("- ")
+            .or_else(|| trimmed.strip_prefix("* "))
+        {
+            let mut spans = vec![Span::raw(format!("{indent}• "))];
+            spans.extend(render_inline(item));
+            lines.push(Line::from(spans));
+        } else if let Some(quote) = trimmed.strip_prefix('>') {
+            lines.push(Line::from(Span::styled(
+                format!("│ {}", quote.trim_start()),
+                Style::default()
+                    .fg(Color::Gray)
+                    .add_modifier(Modifier::ITALIC),
+            )));
+        } else {
+            let mut spans = vec![Span::raw(indent.to_string())];
+            spans.extend(render_inline(trimmed));
+            lines.push(Line::from(spans));
+        }
+    }
+
+    Text::from(lines)
+}
+
+fn render_inline(text: &str) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        let code = rest.find('`');
+        let bold = rest.find("**");
+        let (start, marker, style) = match (code, bold) {
+            (Some(c), Some(b)) if b < c => (b, "**", Style::default().add_modifier(Modifier::BOLD)),
+            (Some(c), _) => (c, "`", Style::default().fg(Color::Yellow)),
+            (None, Some(b)) => (b, "**", Style::default().add_modifier(Modifier::BOLD)),
+            (None, None) => break,
+        };
+
+        let after = &rest[start + marker.len()..];
+        let Some(end) = after.find(marker) else {
+            break;
+        };
+        if start > 0 {
+            spans.push(Span::raw(rest[..start].to_string()));
+        }
+        spans.push(Span::styled(after[..end].to_string(), style));
+        rest = &after[end + marker.len()..];
+    }
+
+    if !rest.is_empty() {
+        spans.push(Span::raw(rest.to_string()));
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("", "feature/login"), Some(0));
+        assert!(fuzzy_score("flog", "feature/login").is_some());
+        assert!(fuzzy_score("FLOG", "feature/login").is_some());
+        assert_eq!(fuzzy_score("golf", "feature/login"), None);
+
+        // Consecutive and word-start matches rank higher
+        let word_start = fuzzy_score("login", "feature/login").unwrap();
+        let scattered = fuzzy_score("login", "lots-of-going-in").unwrap();
+        assert!(word_start > scattered);
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let text = render_markdown("# Title\n\n- item with `code`\n```\nlet x;\n```\n> quote");
+        let lines: Vec<String> = text
+            .lines
+            .iter()
+            .map(|line| line.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect();
+        assert_eq!(
+            lines,
+            vec!["Title", "", "• item with code", "  let x;", "│ quote"]
+        );
+        assert!(
+            text.lines[0].spans[0]
+                .style
+                .add_modifier
+                .contains(Modifier::BOLD)
+        );
+        assert_eq!(text.lines[2].spans[2].style.fg, Some(Color::Yellow));
+    }
+
+    #[test]
+    fn test_commit_only_current_branch() {
+        let mut app = App::new(120);
+        app.current_branch = "feature/current".to_string();
+        app.uncommitted = true;
+        assert_eq!(app.commit_refusal("feature/current"), None);
+        assert_eq!(
+            app.commit_refusal("feature/other"),
+            Some("Descriptions of other branches are committed when edited")
+        );
+
+        app.uncommitted = false;
+        assert_eq!(
+            app.commit_refusal("feature/current"),
+            Some("Nothing to commit")
+        );
+    }
+
+    #[test]
+    fn test_render_inline_unterminated() {
+        let spans = render_inline("**bold** and `open");
+        let contents: Vec<&str> = spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(contents, vec!["bold", " and `open"]);
+    }
+}
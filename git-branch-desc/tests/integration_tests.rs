@@ -1,5 +1,7 @@
 use anyhow::Result;
-use git_branch_desc::GitBranchDescManager;
+use git_branch_desc::bulk::{self, BulkEntry, BulkOutcome};
+use git_branch_desc::push::PushError;
+use git_branch_desc::{GitBranchDescManager, InputSource};
 use serial_test::serial;
 use std::fs;
 use std::process::Command;
@@ -70,6 +72,34 @@ impl TestRepo {
         Ok(())
     }
 
+    /// Adds a bare repository as `origin`; the returned directory must outlive the test.
+    fn add_remote(&self) -> Result<TempDir> {
+        let remote_dir = TempDir::new()?;
+        Command::new("git")
+            .args(["init", "--bare"])
+            .current_dir(remote_dir.path())
+            .output()?;
+        Command::new("git")
+            .args([
+                "remote",
+                "add",
+                "origin",
+                remote_dir.path().to_str().unwrap(),
+            ])
+            .current_dir(&self.repo_path)
+            .output()?;
+        Ok(remote_dir)
+    }
+
+    /// Sets a local `git config` value, e.g. the AI settings a repository file cannot set.
+    fn git_config(&self, key: &str, value: &str) -> Result<()> {
+        Command::new("git")
+            .args(["config", key, value])
+            .current_dir(&self.repo_path)
+            .output()?;
+        Ok(())
+    }
+
     fn set_working_directory(&self) {
         std::env::set_current_dir(&self.repo_path).unwrap();
     }
@@ -421,6 +451,1504 @@ fn test_edit_description_different_branch() -> Result<()> {
     Ok(())
 }
 
+#[test]
+#[serial]
+fn test_branch_commits_and_diff_content() -> Result<()> {
+    let test_repo = TestRepo::new()?;
+    let original_dir = std::env::current_dir()?;
+
+    test_repo.set_working_directory();
+
+    // develop is the default parent branch
+    test_repo.create_branch("develop")?;
+    test_repo.create_branch("feature/login")?;
+
+    fs::write(
+        std::path::Path::new(&test_repo.repo_path).join("login.rs"),
+        "fn login() {}\n",
+    )?;
+    Command::new("git")
+        .args(["add", "login.rs"])
+        .current_dir(&test_repo.repo_path)
+        .output()?;
+    Command::new("git")
+        .args([
+            "commit",
+            "-m",
+            "Add login handler",
+            "-m",
+            "Supports password auth",
+        ])
+        .current_dir(&test_repo.repo_path)
+        .output()?;
+
+    // Description commits are not part of the branch's own work
+    test_repo
+        .manager
+        .write_current_branch_description("Login feature")?;
+    test_repo
+        .manager
+        .commit_current_branch_changes("feature/login", false, false)?;
+
+    assert_eq!(
+        test_repo.manager.get_parent_branch("feature/login")?,
+        "develop"
+    );
+
+    let commits = test_repo
+        .manager
+        .get_branch_commits_content("feature/login")?;
+    assert_eq!(commits, "- Add login handler\n  Supports password auth");
+
+    let diff = test_repo.manager.get_branch_diff_content("feature/login")?;
+    assert!(diff.contains("diff --git a/login.rs b/login.rs"));
+    assert!(diff.contains("+fn login() {}"));
+    assert!(!diff.contains("BRANCHREADME.md"));
+
+    // A branch without own commits has nothing to describe
+    assert!(
+        test_repo
+            .manager
+            .get_branch_commits_content("develop")
+            .is_err()
+    );
+
+    // The parent branch can come from gitflow config
+    Command::new("git")
+        .args(["config", "gitflow.branch.feature/login.base", "main"])
+        .current_dir(&test_repo.repo_path)
+        .output()?;
+    assert_eq!(
+        test_repo.manager.get_parent_branch("feature/login")?,
+        "main"
+    );
+
+    std::env::set_current_dir(original_dir)?;
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_combined_input_sources() -> Result<()> {
+    let test_repo = TestRepo::new()?;
+    let original_dir = std::env::current_dir()?;
+
+    test_repo.set_working_directory();
+
+    test_repo.create_branch("develop")?;
+    test_repo.create_branch("feature/combined")?;
+    Command::new("git")
+        .args(["commit", "--allow-empty", "-m", "Wire up export button"])
+        .current_dir(&test_repo.repo_path)
+        .output()?;
+
+    test_repo.manager.edit_description_v2(
+        None,
+        InputSource::Combined(vec![
+            InputSource::CommandLine(Some("Export for the sprint review".to_string())),
+            InputSource::Commits,
+        ]),
+        false,
+        120,
+        false,
+        false,
+        false,
+    )?;
+
+    assert_eq!(
+        test_repo.manager.read_current_branch_description()?,
+        "## Notes\n\nExport for the sprint review\n\n## Commits\n\n- Wire up export button"
+    );
+
+    std::env::set_current_dir(original_dir)?;
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_file_input_source() -> Result<()> {
+    let test_repo = TestRepo::new()?;
+    let original_dir = std::env::current_dir()?;
+
+    test_repo.set_working_directory();
+
+    // Draft saved by a Windows editor: UTF-8 BOM and CRLF line endings
+    let draft = tempfile::NamedTempFile::new()?;
+    fs::write(
+        draft.path(),
+        b"\xEF\xBB\xBFLogin rework\r\n\r\nSplit session handling\r\n",
+    )?;
+
+    test_repo.manager.edit_description_v2(
+        None,
+        InputSource::File(draft.path().to_path_buf()),
+        false,
+        120,
+        true,
+        false,
+        false,
+    )?;
+
+    let current_branch = test_repo.manager.get_current_branch()?;
+    assert_eq!(
+        test_repo
+            .manager
+            .read_branch_description_from_git(&current_branch)?
+            .unwrap_or_default(),
+        "Login rework\n\nSplit session handling"
+    );
+
+    std::env::set_current_dir(original_dir)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+#[serial]
+fn test_editor_input_with_configured_editor() -> Result<()> {
+    // GIT_EDITOR takes precedence over core.editor, so only run when it is not set
+    if std::env::var("GIT_EDITOR").is_ok() {
+        return Ok(());
+    }
+
+    let test_repo = TestRepo::new()?;
+    let original_dir = std::env::current_dir()?;
+
+    test_repo.set_working_directory();
+    test_repo
+        .manager
+        .write_current_branch_description("Old text")?;
+
+    // An editor command with arguments, like `code --wait`; it prepends a heading above the
+    // existing description and keeps the template below the scissors line
+    Command::new("git")
+        .args([
+            "config",
+            "core.editor",
+            r###"sh -c 'printf "## Plan\n\n" | cat - "$0" > "$0.new" && mv "$0.new" "$0"'"###,
+        ])
+        .current_dir(&test_repo.repo_path)
+        .output()?;
+    assert!(test_repo.manager.get_editor_command().starts_with("sh -c"));
+
+    test_repo.manager.edit_description_v2(
+        None,
+        InputSource::Editor,
+        false,
+        120,
+        false,
+        false,
+        false,
+    )?;
+
+    assert_eq!(
+        test_repo.manager.read_current_branch_description()?,
+        "## Plan\n\nOld text"
+    );
+
+    std::env::set_current_dir(original_dir)?;
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_issue_detection_and_metadata() -> Result<()> {
+    let test_repo = TestRepo::new()?;
+    let original_dir = std::env::current_dir()?;
+
+    test_repo.set_working_directory();
+
+    assert_eq!(
+        test_repo
+            .manager
+            .detect_issue_reference("feature/1234-login")?
+            .as_deref(),
+        Some("1234")
+    );
+    assert_eq!(test_repo.manager.detect_issue_reference("develop")?, None);
+
+    // The pattern is configurable per repository
+    Command::new("git")
+        .args(["config", "branchdesc.issuePattern", r"issue-(\d+)"])
+        .current_dir(&test_repo.repo_path)
+        .output()?;
+    assert_eq!(
+        test_repo
+            .manager
+            .detect_issue_reference("feature/issue-77")?
+            .as_deref(),
+        Some("77")
+    );
+
+    // Metadata is preserved when the description text is replaced
+    test_repo
+        .manager
+        .write_current_branch_description("Old text\n\n<!-- issue: #77 -->")?;
+    test_repo.manager.edit_description_v2(
+        None,
+        InputSource::CommandLine(Some("New text".to_string())),
+        false,
+        120,
+        false,
+        false,
+        false,
+    )?;
+    assert_eq!(
+        test_repo.manager.read_current_branch_description()?,
+        "New text\n\n<!-- issue: #77 -->"
+    );
+
+    std::env::set_current_dir(original_dir)?;
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_checkout_and_listing_names() -> Result<()> {
+    let test_repo = TestRepo::new()?;
+    let original_dir = std::env::current_dir()?;
+
+    test_repo.set_working_directory();
+    let main_branch = test_repo.manager.get_current_branch()?;
+
+    test_repo.create_branch("feature/tui")?;
+    test_repo
+        .manager
+        .commit_to_branch("feature/tui", "TUI branch", false, false)?;
+    test_repo.checkout_branch(&main_branch)?;
+
+    let descriptions = test_repo.manager.collect_descriptions(false)?;
+    assert_eq!(descriptions.len(), 1);
+    assert_eq!(descriptions[0].branch, "feature/tui");
+    assert_eq!(
+        test_repo
+            .manager
+            .branch_name_from_listing(&descriptions[0].branch)?,
+        "feature/tui"
+    );
+
+    test_repo.manager.checkout_branch("feature/tui")?;
+    assert_eq!(test_repo.manager.get_current_branch()?, "feature/tui");
+    assert_eq!(
+        test_repo.manager.read_current_branch_description()?,
+        "TUI branch"
+    );
+
+    assert!(test_repo.manager.checkout_branch("missing").is_err());
+
+    std::env::set_current_dir(original_dir)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_prompt_templates_with_command_provider() -> Result<()> {
+    let test_repo = TestRepo::new()?;
+    if std::env::vars().any(|(key, _)| key.starts_with("BRANCHDESC_AI_")) {
+        return Ok(());
+    }
+
+    // The command provider echoes the rendered system prompt back
+    test_repo.git_config("branchdesc.ai.provider", "command")?;
+    test_repo.git_config("branchdesc.ai.command", "head -n 1")?;
+    fs::write(
+        std::path::Path::new(&test_repo.repo_path).join(".branchdesc.toml"),
+        r#"
+[ai]
+language = "German"
+
+[prompts.bullets]
+system = "Bullets in {language} for {branch}: {issue_title}"
+"#,
+    )?;
+
+    let vars = git_branch_desc::ai::PromptVars {
+        branch: "feature/42-export".to_string(),
+        issue_title: Some("CSV export".to_string()),
+    };
+    let summary = test_repo
+        .manager
+        .ai_summarize_with_prompt("Some content", &vars, 10)?;
+    assert!(summary.contains("in German"));
+
+    let manager = GitBranchDescManager::new(&test_repo.repo_path)?.with_ai_overrides(
+        git_branch_desc::ai::AiOverrides {
+            prompt: Some("bullets".to_string()),
+            ..Default::default()
+        },
+    );
+    let summary = manager.ai_summarize_with_prompt("Some content", &vars, 10)?;
+    assert_eq!(
+        summary,
+        "Bullets in German for feature/42-export: CSV export"
+    );
+
+    let manager = GitBranchDescManager::new(&test_repo.repo_path)?.with_ai_overrides(
+        git_branch_desc::ai::AiOverrides {
+            prompt: Some("missing".to_string()),
+            ..Default::default()
+        },
+    );
+    assert!(
+        manager
+            .ai_summarize_with_prompt("Some content", &vars, 10)
+            .is_err()
+    );
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+#[serial]
+fn test_mr_text_and_commit_message() -> Result<()> {
+    let test_repo = TestRepo::new()?;
+    if std::env::vars().any(|(key, _)| key.starts_with("BRANCHDESC_AI_")) {
+        return Ok(());
+    }
+    let original_dir = std::env::current_dir()?;
+    test_repo.set_working_directory();
+
+    // The command provider echoes the section headings of the context it gets
+    test_repo.git_config("branchdesc.ai.provider", "command")?;
+    test_repo.git_config("branchdesc.ai.command", "grep '^## '")?;
+
+    test_repo.create_branch("develop")?;
+    test_repo.create_branch("feature/login")?;
+    fs::write(
+        std::path::Path::new(&test_repo.repo_path).join("login.rs"),
+        "fn login() {}\n",
+    )?;
+    Command::new("git")
+        .args(["add", "login.rs"])
+        .current_dir(&test_repo.repo_path)
+        .output()?;
+    Command::new("git")
+        .args(["commit", "-m", "Add login handler"])
+        .current_dir(&test_repo.repo_path)
+        .output()?;
+    test_repo
+        .manager
+        .write_current_branch_description("Login feature\n\n<!-- issue: #12 -->\n")?;
+
+    let text = test_repo.manager.generate_mr_text(None, 10)?;
+    assert_eq!(
+        text,
+        "## Branch description\n## Commits\n## Changes\n\nRelated to #12"
+    );
+
+    // Staged changes replace the branch diff
+    fs::write(
+        std::path::Path::new(&test_repo.repo_path).join("logout.rs"),
+        "fn logout() {}\n",
+    )?;
+    Command::new("git")
+        .args(["add", "logout.rs"])
+        .current_dir(&test_repo.repo_path)
+        .output()?;
+    let message = test_repo.manager.generate_commit_message(None, 10)?;
+    assert_eq!(
+        message,
+        "## Branch description\n## Commits\n## Staged changes\n\nRefs: #12"
+    );
+
+    // Without a parent branch there is nothing to compare against
+    let message = test_repo
+        .manager
+        .generate_commit_message(Some("develop".to_string()), 10);
+    assert!(message.is_err());
+
+    std::env::set_current_dir(original_dir)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_ai_summary_cache() -> Result<()> {
+    let test_repo = TestRepo::new()?;
+    if std::env::vars().any(|(key, _)| key.starts_with("BRANCHDESC_AI_")) {
+        return Ok(());
+    }
+
+    // The command provider logs each call, so cache hits are visible
+    let log = std::path::Path::new(&test_repo.repo_path).join("calls.log");
+    test_repo.git_config("branchdesc.ai.provider", "command")?;
+    test_repo.git_config(
+        "branchdesc.ai.command",
+        &format!("echo call >> '{}'; echo Summary", log.display()),
+    )?;
+    let calls = || fs::read_to_string(&log).map_or(0, |log| log.lines().count());
+
+    let summary = test_repo.manager.ai_summarize_content("Some content", 10)?;
+    assert_eq!(summary, "Summary");
+    assert_eq!(
+        test_repo.manager.ai_summarize_content("Some content", 10)?,
+        "Summary"
+    );
+    assert_eq!(calls(), 1);
+
+    // Other content is a cache miss
+    test_repo
+        .manager
+        .ai_summarize_content("Other content", 10)?;
+    assert_eq!(calls(), 2);
+
+    let manager = GitBranchDescManager::new(&test_repo.repo_path)?.with_ai_overrides(
+        git_branch_desc::ai::AiOverrides {
+            no_cache: true,
+            ..Default::default()
+        },
+    );
+    manager.ai_summarize_content("Some content", 10)?;
+    assert_eq!(calls(), 3);
+
+    test_repo.manager.prune_ai_cache(None)?;
+    test_repo.manager.ai_summarize_content("Some content", 10)?;
+    assert_eq!(calls(), 4);
+
+    // Other settings of the same provider are a cache miss too
+    test_repo.git_config("branchdesc.ai.temperature", "0.9")?;
+    test_repo.manager.ai_summarize_content("Some content", 10)?;
+    assert_eq!(calls(), 5);
+
+    Ok(())
+}
+
+#[test]
+fn test_builtin_summarizer_fallback() -> Result<()> {
+    let test_repo = TestRepo::new()?;
+    if std::env::vars().any(|(key, _)| key.starts_with("BRANCHDESC_AI_")) {
+        return Ok(());
+    }
+
+    // Nothing listens on the discard port, so the provider cannot be reached
+    test_repo.git_config("branchdesc.ai.provider", "openai")?;
+    test_repo.git_config("branchdesc.ai.endpoint", "http://127.0.0.1:9/v1")?;
+
+    let issue = "Add CSV export\n\nUsers need to export their reports as CSV files.";
+    let vars = git_branch_desc::ai::PromptVars {
+        branch: "feature/42-export".to_string(),
+        issue_title: Some("Add CSV export".to_string()),
+    };
+    let summary = test_repo
+        .manager
+        .ai_summarize_with_prompt(issue, &vars, 10)?;
+    assert_eq!(
+        summary,
+        "Add CSV export\n\nUsers need to export their reports as CSV files."
+    );
+
+    let manager = GitBranchDescManager::new(&test_repo.repo_path)?.with_ai_overrides(
+        git_branch_desc::ai::AiOverrides {
+            summarizer: Some(git_branch_desc::ai::SummarizerKind::Ai),
+            ..Default::default()
+        },
+    );
+    assert!(manager.ai_summarize_with_prompt(issue, &vars, 10).is_err());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_push_errors_and_force_with_lease() -> Result<()> {
+    let test_repo = TestRepo::new()?;
+    let remote_dir = test_repo.add_remote()?;
+    let original_dir = std::env::current_dir()?;
+    test_repo.set_working_directory();
+    let main_branch = test_repo.manager.get_current_branch()?;
+
+    test_repo.create_branch("feature/push")?;
+    test_repo.checkout_branch(&main_branch)?;
+    test_repo
+        .manager
+        .commit_to_branch("feature/push", "First description", false, true)?;
+
+    // Someone else pushes to the branch from another clone
+    let other_dir = TempDir::new()?;
+    let other_path = other_dir.path().to_str().unwrap();
+    Command::new("git")
+        .args(["clone", "-q", "-b", "feature/push"])
+        .args([remote_dir.path().to_str().unwrap(), other_path])
+        .output()?;
+    fs::write(other_dir.path().join("other.txt"), "other")?;
+    for args in [
+        vec!["add", "other.txt"],
+        vec![
+            "-c",
+            "user.name=Other",
+            "-c",
+            "user.email=other@example.com",
+            "commit",
+            "-qm",
+            "Other change",
+        ],
+        vec!["push", "-q", "origin", "feature/push"],
+    ] {
+        Command::new("git")
+            .args(&args)
+            .current_dir(other_path)
+            .output()?;
+    }
+
+    let err = test_repo
+        .manager
+        .commit_to_branch("feature/push", "Second description", false, true)
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<PushError>(),
+        Some(PushError::NonFastForward { .. })
+    ));
+
+    // The lease is stale until the other change has been fetched
+    let manager = GitBranchDescManager::new(&test_repo.repo_path)?.with_force_with_lease(true);
+    let err = manager.push_branch("feature/push").unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<PushError>(),
+        Some(PushError::StaleLease { .. })
+    ));
+
+    Command::new("git")
+        .args(["fetch", "-q", "origin"])
+        .current_dir(&test_repo.repo_path)
+        .output()?;
+    manager.push_branch("feature/push")?;
+
+    // The own previous push does not make the lease stale
+    manager.commit_to_branch("feature/push", "Third description", true, true)?;
+
+    let remote_head = Command::new("git")
+        .args(["rev-parse", "feature/push"])
+        .current_dir(remote_dir.path())
+        .output()?;
+    let local_head = Command::new("git")
+        .args(["rev-parse", "feature/push"])
+        .current_dir(&test_repo.repo_path)
+        .output()?;
+    assert_eq!(remote_head.stdout, local_head.stdout);
+
+    std::env::set_current_dir(original_dir)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+#[serial]
+fn test_signed_description_commits() -> Result<()> {
+    let test_repo = TestRepo::new()?;
+    let original_dir = std::env::current_dir()?;
+    test_repo.set_working_directory();
+    let main_branch = test_repo.manager.get_current_branch()?;
+
+    let key_dir = TempDir::new()?;
+    let key = key_dir.path().join("id_ed25519");
+    let keygen = Command::new("ssh-keygen")
+        .args(["-q", "-t", "ed25519", "-N", "", "-C", "test", "-f"])
+        .arg(&key)
+        .output();
+    if !keygen.is_ok_and(|output| output.status.success()) {
+        // ssh-keygen is not installed
+        std::env::set_current_dir(original_dir)?;
+        return Ok(());
+    }
+    let public_key = fs::read_to_string(key.with_extension("pub"))?;
+    let allowed_signers = key_dir.path().join("allowed_signers");
+    fs::write(&allowed_signers, format!("test@example.com {public_key}"))?;
+
+    for (name, value) in [
+        ("commit.gpgsign", "true"),
+        ("gpg.format", "ssh"),
+        ("user.signingkey", key.to_str().unwrap()),
+        (
+            "gpg.ssh.allowedSignersFile",
+            allowed_signers.to_str().unwrap(),
+        ),
+    ] {
+        Command::new("git")
+            .args(["config", name, value])
+            .current_dir(&test_repo.repo_path)
+            .output()?;
+    }
+    let verify = |rev: &str| -> Result<bool> {
+        Ok(Command::new("git")
+            .args(["verify-commit", rev])
+            .current_dir(&test_repo.repo_path)
+            .output()?
+            .status
+            .success())
+    };
+
+    // Non-checked-out branch
+    test_repo.create_branch("feature/signed")?;
+    test_repo.checkout_branch(&main_branch)?;
+    test_repo
+        .manager
+        .commit_to_branch("feature/signed", "Signed description", false, false)?;
+    assert!(verify("feature/signed")?);
+
+    // Current branch
+    test_repo
+        .manager
+        .write_current_branch_description("Signed on main")?;
+    test_repo
+        .manager
+        .commit_current_branch_changes(&main_branch, false, false)?;
+    assert!(verify("HEAD")?);
+    assert!(!verify("HEAD~1")?);
+
+    // Signing errors abort the commit
+    Command::new("git")
+        .args(["config", "user.signingkey", "/missing/key"])
+        .current_dir(&test_repo.repo_path)
+        .output()?;
+    assert!(
+        test_repo
+            .manager
+            .commit_to_branch("feature/signed", "Unsigned", true, false)
+            .is_err()
+    );
+    assert_eq!(
+        test_repo
+            .manager
+            .read_branch_description_from_git("feature/signed")?
+            .as_deref(),
+        Some("Signed description")
+    );
+
+    std::env::set_current_dir(original_dir)?;
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_description_commit_message_template() -> Result<()> {
+    let test_repo = TestRepo::new()?;
+    let original_dir = std::env::current_dir()?;
+    test_repo.set_working_directory();
+    let main_branch = test_repo.manager.get_current_branch()?;
+    let last_message = || -> Result<String> {
+        let output = Command::new("git")
+            .args(["log", "-1", "--format=%B", "feature/msg"])
+            .current_dir(&test_repo.repo_path)
+            .output()?;
+        Ok(String::from_utf8(output.stdout)?.trim_end().to_string())
+    };
+
+    test_repo.create_branch("feature/msg")?;
+    test_repo.checkout_branch(&main_branch)?;
+    test_repo
+        .manager
+        .commit_to_branch("feature/msg", "First draft", false, false)?;
+    assert_eq!(last_message()?, "Add branch description [skip ci]");
+
+    for args in [
+        ["branchdesc.commit.template", "docs({branch}): {summary}"],
+        ["branchdesc.commit.skipCi", "false"],
+        ["branchdesc.commit.signoff", "true"],
+    ] {
+        Command::new("git")
+            .arg("config")
+            .args(args)
+            .current_dir(&test_repo.repo_path)
+            .output()?;
+    }
+    Command::new("git")
+        .args([
+            "config",
+            "--add",
+            "branchdesc.commit.trailer",
+            "Refs: {issue}",
+        ])
+        .current_dir(&test_repo.repo_path)
+        .output()?;
+
+    test_repo.manager.commit_to_branch(
+        "feature/msg",
+        "Message templates\n\n<!-- issue: #123 -->",
+        true,
+        false,
+    )?;
+    assert_eq!(
+        last_message()?,
+        "docs(feature/msg): Message templates\n\nRefs: #123\nSigned-off-by: Test User <test@example.com>"
+    );
+
+    std::env::set_current_dir(original_dir)?;
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_amend_description_commits() -> Result<()> {
+    let test_repo = TestRepo::new()?;
+    let _remote_dir = test_repo.add_remote()?;
+    let original_dir = std::env::current_dir()?;
+    test_repo.set_working_directory();
+    let main_branch = test_repo.manager.get_current_branch()?;
+    let log = |branch: &str| -> Result<Vec<String>> {
+        let output = Command::new("git")
+            .args(["log", "--format=%s", branch])
+            .current_dir(&test_repo.repo_path)
+            .output()?;
+        Ok(String::from_utf8(output.stdout)?
+            .lines()
+            .map(str::to_string)
+            .collect())
+    };
+    let manager = GitBranchDescManager::new(&test_repo.repo_path)?.with_amend(true);
+
+    // Non-checked-out branch: the first commit keeps its "Add" message
+    test_repo.create_branch("feature/amend")?;
+    test_repo.checkout_branch(&main_branch)?;
+    manager.commit_to_branch("feature/amend", "Draft", false, false)?;
+    manager.commit_to_branch("feature/amend", "Final", true, false)?;
+    assert_eq!(
+        log("feature/amend")?,
+        ["Add branch description [skip ci]", "Initial commit"]
+    );
+    assert_eq!(
+        manager
+            .read_branch_description_from_git("feature/amend")?
+            .as_deref(),
+        Some("Final")
+    );
+
+    // Pushed commits are not rewritten
+    manager.push_branch("feature/amend")?;
+    manager.commit_to_branch("feature/amend", "After push", true, false)?;
+    assert_eq!(log("feature/amend")?.len(), 3);
+
+    // `edit --push` then `edit --amend --push` adds a commit and pushes it
+    manager.commit_to_branch("feature/amend", "Pushed", true, true)?;
+    manager.commit_to_branch("feature/amend", "Pushed again", true, true)?;
+    assert_eq!(log("feature/amend")?.len(), 4);
+    assert_eq!(
+        log("origin/feature/amend")?,
+        log("feature/amend")?,
+        "the remote-tracking branch follows the push"
+    );
+
+    // Current branch
+    test_repo.create_branch("feature/current")?;
+    manager.write_current_branch_description("One")?;
+    manager.commit_current_branch_changes("feature/current", false, false)?;
+    manager.write_current_branch_description("Two")?;
+    manager.commit_current_branch_changes("feature/current", true, false)?;
+    assert_eq!(
+        log("feature/current")?,
+        ["Add branch description [skip ci]", "Initial commit"]
+    );
+    assert_eq!(
+        manager
+            .read_branch_description_from_git("feature/current")?
+            .as_deref(),
+        Some("Two")
+    );
+
+    // `edit --amend` on the current branch commits without --commit
+    manager.edit_description_v2(
+        None,
+        InputSource::CommandLine(Some("Two, reworded".to_string())),
+        false,
+        120,
+        false,
+        false,
+        true,
+    )?;
+    assert_eq!(
+        log("feature/current")?,
+        ["Add branch description [skip ci]", "Initial commit"]
+    );
+    assert_eq!(
+        manager
+            .read_branch_description_from_git("feature/current")?
+            .as_deref(),
+        Some("Two, reworded")
+    );
+
+    // Only description-only commits are replaced
+    fs::write("code.rs", "fn main() {}\n")?;
+    fs::write("BRANCHREADME.md", "Three")?;
+    Command::new("git")
+        .args(["add", "code.rs", "BRANCHREADME.md"])
+        .current_dir(&test_repo.repo_path)
+        .output()?;
+    Command::new("git")
+        .args(["commit", "-qm", "Code and description"])
+        .current_dir(&test_repo.repo_path)
+        .output()?;
+    manager.write_current_branch_description("Four")?;
+    manager.commit_current_branch_changes("feature/current", true, false)?;
+    assert_eq!(log("feature/current")?.len(), 4);
+
+    std::env::set_current_dir(original_dir)?;
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_edit_remote_only_branch() -> Result<()> {
+    let test_repo = TestRepo::new()?;
+    let remote_dir = test_repo.add_remote()?;
+    let original_dir = std::env::current_dir()?;
+    test_repo.set_working_directory();
+    let main_branch = test_repo.manager.get_current_branch()?;
+    let git = |args: &[&str]| -> Result<String> {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(&test_repo.repo_path)
+            .output()?;
+        Ok(String::from_utf8(output.stdout)?.trim().to_string())
+    };
+
+    // A colleague's branch that only exists on the remote
+    test_repo.create_branch("feature/colleague")?;
+    git(&["push", "-q", "origin", "feature/colleague"])?;
+    test_repo.checkout_branch(&main_branch)?;
+    git(&["branch", "-q", "-D", "feature/colleague"])?;
+    let remote_tip = git(&["rev-parse", "origin/feature/colleague"])?;
+
+    // Without --push nothing may reach the shared branch
+    let err = test_repo
+        .manager
+        .edit_description_v2(
+            Some("feature/colleague".to_string()),
+            InputSource::CommandLine(Some("Reviewed by the team lead".to_string())),
+            false,
+            120,
+            false,
+            false,
+            true,
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("--push"), "{err}");
+    assert!(
+        test_repo
+            .manager
+            .commit_to_branch("feature/colleague", "Reviewed", false, false)
+            .is_err()
+    );
+    assert_eq!(
+        git(&["ls-remote", "origin", "refs/heads/feature/colleague"])?
+            .split_whitespace()
+            .next(),
+        Some(remote_tip.as_str())
+    );
+
+    test_repo.manager.edit_description_v2(
+        Some("feature/colleague".to_string()),
+        InputSource::CommandLine(Some("Reviewed by the team lead".to_string())),
+        false,
+        120,
+        false,
+        true,
+        true,
+    )?;
+
+    assert_eq!(git(&["branch", "--list", "feature/colleague"])?, "");
+    assert_eq!(git(&["for-each-ref", "refs/branchdesc"])?, "");
+    let on_remote = Command::new("git")
+        .args(["show", "feature/colleague:BRANCHREADME.md"])
+        .current_dir(remote_dir.path())
+        .output()?;
+    assert_eq!(
+        String::from_utf8(on_remote.stdout)?,
+        "Reviewed by the team lead"
+    );
+    assert_eq!(
+        test_repo
+            .manager
+            .read_branch_description_from_git("feature/colleague")?
+            .as_deref(),
+        Some("Reviewed by the team lead")
+    );
+
+    // The name shown by `list` works too
+    test_repo.manager.edit_description_v2(
+        Some("origin/feature/colleague".to_string()),
+        InputSource::CommandLine(Some("Second note".to_string())),
+        false,
+        120,
+        false,
+        true,
+        true,
+    )?;
+    assert_eq!(
+        git(&["log", "-1", "--format=%s", "origin/feature/colleague"])?,
+        "Update branch description [skip ci]"
+    );
+    assert_eq!(git(&["branch", "--list", "feature/colleague"])?, "");
+
+    std::env::set_current_dir(original_dir)?;
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_bulk_edit_with_atomic_push() -> Result<()> {
+    let test_repo = TestRepo::new()?;
+    let remote_dir = test_repo.add_remote()?;
+    let original_dir = std::env::current_dir()?;
+    test_repo.set_working_directory();
+    let main_branch = test_repo.manager.get_current_branch()?;
+    let git = |args: &[&str]| -> Result<String> {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(&test_repo.repo_path)
+            .output()?;
+        Ok(String::from_utf8(output.stdout)?.trim().to_string())
+    };
+    let on_remote = |branch: &str| -> Result<String> {
+        let output = Command::new("git")
+            .args(["show", &format!("{branch}:BRANCHREADME.md")])
+            .current_dir(remote_dir.path())
+            .output()?;
+        Ok(String::from_utf8(output.stdout)?)
+    };
+
+    test_repo.create_branch("feature/a")?;
+    git(&["push", "-q", "origin", "feature/a"])?;
+    test_repo.create_branch("feature/colleague")?;
+    git(&["push", "-q", "origin", "feature/colleague"])?;
+    test_repo.checkout_branch(&main_branch)?;
+    git(&["branch", "-q", "-D", "feature/colleague"])?;
+    git(&["push", "-q", "origin", &main_branch])?;
+    test_repo
+        .manager
+        .commit_to_branch("feature/a", "Same", false, false)?;
+
+    let json = format!(
+        r#"{{"{main_branch}": "Main line", "feature/a": "Same", "origin/feature/colleague": "Reviewed", "feature/missing": "Nope"}}"#
+    );
+    let outcomes = test_repo
+        .manager
+        .bulk_edit(bulk::parse_json(&json)?, true)?;
+    assert_eq!(outcomes.len(), 4);
+    assert!(outcomes.contains(&(main_branch.clone(), BulkOutcome::Pushed)));
+    assert!(outcomes.contains(&("feature/a".to_string(), BulkOutcome::Skipped)));
+    assert!(outcomes.contains(&("feature/colleague".to_string(), BulkOutcome::Pushed)));
+    assert!(matches!(
+        outcomes
+            .iter()
+            .find(|(branch, _)| branch == "feature/missing"),
+        Some((_, BulkOutcome::Failed(_)))
+    ));
+
+    assert_eq!(on_remote(&main_branch)?, "Main line");
+    assert_eq!(on_remote("feature/colleague")?, "Reviewed");
+    assert_eq!(git(&["branch", "--list", "feature/colleague"])?, "");
+    assert_eq!(git(&["for-each-ref", "refs/branchdesc"])?, "");
+
+    // Someone else pushed to feature/a: neither branch is pushed
+    let tree = git(&["rev-parse", "HEAD^{tree}"])?;
+    let foreign = git(&["commit-tree", &tree, "-p", "HEAD", "-m", "Foreign"])?;
+    git(&[
+        "push",
+        "-q",
+        "-f",
+        "origin",
+        &format!("{foreign}:refs/heads/feature/a"),
+    ])?;
+    let remote_main = git(&["rev-parse", &format!("origin/{main_branch}")])?;
+
+    let entries = vec![
+        BulkEntry::new("feature/a", "Changed"),
+        BulkEntry::new(&main_branch, "Main line, again"),
+    ];
+    let outcomes = test_repo.manager.bulk_edit(entries, true)?;
+    assert!(matches!(
+        &outcomes[0].1,
+        BulkOutcome::Failed(message) if message.contains("Pull first")
+    ));
+    assert!(matches!(
+        &outcomes[1].1,
+        BulkOutcome::Failed(message) if message.contains("atomic push")
+    ));
+    assert_eq!(
+        git(&["ls-remote", "origin", &format!("refs/heads/{main_branch}")])?
+            .split_whitespace()
+            .next(),
+        Some(remote_main.as_str())
+    );
+    // The local commits are kept
+    assert_eq!(
+        test_repo
+            .manager
+            .read_branch_description_from_git("feature/a")?
+            .as_deref(),
+        Some("Changed")
+    );
+
+    std::env::set_current_dir(original_dir)?;
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_bulk_edit_reports_failed_push_per_branch() -> Result<()> {
+    let test_repo = TestRepo::new()?;
+    let original_dir = std::env::current_dir()?;
+    test_repo.set_working_directory();
+    let main_branch = test_repo.manager.get_current_branch()?;
+    test_repo.create_branch("feature/a")?;
+    test_repo.checkout_branch(&main_branch)?;
+    let missing_remote = TempDir::new()?;
+    Command::new("git")
+        .args([
+            "remote",
+            "add",
+            "origin",
+            missing_remote.path().join("gone.git").to_str().unwrap(),
+        ])
+        .current_dir(&test_repo.repo_path)
+        .output()?;
+
+    let entries = vec![
+        BulkEntry::new(&main_branch, "Main line"),
+        BulkEntry::new("feature/a", "Feature"),
+    ];
+    let outcomes = test_repo.manager.bulk_edit(entries, true)?;
+    assert_eq!(outcomes.len(), 2);
+    for (branch, outcome) in &outcomes {
+        assert!(
+            matches!(outcome, BulkOutcome::Failed(message)
+                if message.contains("failed") && message.contains("remains on the local branch")),
+            "{branch}: {outcome}"
+        );
+    }
+    assert_eq!(
+        test_repo
+            .manager
+            .read_branch_description_from_git("feature/a")?
+            .as_deref(),
+        Some("Feature")
+    );
+
+    std::env::set_current_dir(original_dir)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+#[serial]
+fn test_bulk_push_rejected_by_hook_updates_nothing() -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let test_repo = TestRepo::new()?;
+    let remote_dir = test_repo.add_remote()?;
+    let original_dir = std::env::current_dir()?;
+    test_repo.set_working_directory();
+    let main_branch = test_repo.manager.get_current_branch()?;
+    let remote_tip = |branch: &str| -> Result<String> {
+        let output = Command::new("git")
+            .args(["rev-parse", branch])
+            .current_dir(remote_dir.path())
+            .output()?;
+        Ok(String::from_utf8(output.stdout)?.trim().to_string())
+    };
+
+    test_repo.create_branch("feature/protected")?;
+    test_repo.checkout_branch(&main_branch)?;
+    Command::new("git")
+        .args(["push", "-q", "origin", &main_branch, "feature/protected"])
+        .current_dir(&test_repo.repo_path)
+        .output()?;
+    let main_before = remote_tip(&main_branch)?;
+    let protected_before = remote_tip("feature/protected")?;
+
+    // The server only declines feature/protected, the atomic push must not update main either
+    let hook = remote_dir.path().join("hooks").join("pre-receive");
+    fs::write(
+        &hook,
+        "#!/bin/sh\nwhile read old new ref; do\n  [ \"$ref\" = refs/heads/feature/protected ] && exit 1\ndone\nexit 0\n",
+    )?;
+    fs::set_permissions(&hook, fs::Permissions::from_mode(0o755))?;
+
+    let entries = vec![
+        BulkEntry::new(&main_branch, "Main line"),
+        BulkEntry::new("feature/protected", "Protected"),
+    ];
+    let outcomes = test_repo.manager.bulk_edit(entries, true)?;
+    for (branch, outcome) in &outcomes {
+        assert!(
+            matches!(outcome, BulkOutcome::Failed(message) if message.contains("server hook")),
+            "{branch}: {outcome}"
+        );
+    }
+    assert_eq!(remote_tip(&main_branch)?, main_before);
+    assert_eq!(remote_tip("feature/protected")?, protected_before);
+
+    std::env::set_current_dir(original_dir)?;
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_edit_dry_run() -> Result<()> {
+    let test_repo = TestRepo::new()?;
+    let original_dir = std::env::current_dir()?;
+    test_repo.set_working_directory();
+    let main_branch = test_repo.manager.get_current_branch()?;
+    let git = |args: &[&str]| -> Result<String> {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(&test_repo.repo_path)
+            .output()?;
+        Ok(String::from_utf8(output.stdout)?.trim().to_string())
+    };
+
+    test_repo.create_branch("feature/preview")?;
+    test_repo.checkout_branch(&main_branch)?;
+    test_repo
+        .manager
+        .commit_to_branch("feature/preview", "Old text", false, false)?;
+    let refs_before = git(&["for-each-ref"])?;
+
+    let manager = GitBranchDescManager::new(&test_repo.repo_path)?.with_dry_run(true);
+    manager.edit_description_v2(
+        None,
+        InputSource::CommandLine(Some("New description".to_string())),
+        false,
+        120,
+        true,
+        true,
+        false,
+    )?;
+    // No confirmation prompt for other branches and no remote needed to preview a push
+    manager.edit_description_v2(
+        Some("feature/preview".to_string()),
+        InputSource::CommandLine(Some("New text".to_string())),
+        false,
+        120,
+        false,
+        true,
+        false,
+    )?;
+
+    assert!(
+        !std::path::Path::new(&test_repo.repo_path)
+            .join("BRANCHREADME.md")
+            .exists()
+    );
+    assert_eq!(git(&["for-each-ref"])?, refs_before);
+    assert_eq!(git(&["status", "--porcelain"])?, "");
+    assert_eq!(
+        test_repo
+            .manager
+            .read_branch_description_from_git("feature/preview")?
+            .as_deref(),
+        Some("Old text")
+    );
+
+    // The AI summary is shown but not cached
+    test_repo.git_config("branchdesc.ai.provider", "command")?;
+    test_repo.git_config("branchdesc.ai.command", "echo Previewed summary")?;
+    let input_dir = TempDir::new()?;
+    let input = input_dir.path().join("notes.txt");
+    fs::write(&input, "Notes to summarize")?;
+    manager.edit_description_v2(
+        None,
+        InputSource::File(input),
+        true,
+        120,
+        true,
+        false,
+        false,
+    )?;
+    let cache_dir = std::path::Path::new(&test_repo.repo_path).join(".git/branchdesc-cache");
+    assert!(!cache_dir.exists() || fs::read_dir(&cache_dir)?.next().is_none());
+    assert_eq!(git(&["status", "--porcelain"])?, "");
+
+    std::env::set_current_dir(original_dir)?;
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_subdirectory_worktree_and_bare_repository() -> Result<()> {
+    let test_repo = TestRepo::new()?;
+    let original_dir = std::env::current_dir()?;
+    let main_branch = test_repo.manager.get_current_branch()?;
+    let repo_path = std::path::Path::new(&test_repo.repo_path);
+    let git_in = |dir: &std::path::Path, args: &[&str]| -> Result<String> {
+        let output = Command::new("git").args(args).current_dir(dir).output()?;
+        Ok(String::from_utf8(output.stdout)?.trim().to_string())
+    };
+
+    // Started from a subdirectory, the description still lives at the root
+    fs::create_dir(repo_path.join("src"))?;
+    std::env::set_current_dir(repo_path.join("src"))?;
+    let manager = GitBranchDescManager::new(".")?;
+    manager.write_current_branch_description("From a subdirectory")?;
+    manager.commit_current_branch_changes(&main_branch, false, false)?;
+    assert!(repo_path.join("BRANCHREADME.md").exists());
+    assert!(!repo_path.join("src/BRANCHREADME.md").exists());
+    assert_eq!(
+        git_in(repo_path, &["show", "HEAD:BRANCHREADME.md"])?,
+        "From a subdirectory"
+    );
+
+    // A linked worktree has its own HEAD
+    let worktree_dir = TempDir::new()?;
+    let worktree_path = worktree_dir.path().join("wt");
+    git_in(
+        repo_path,
+        &[
+            "worktree",
+            "add",
+            "-q",
+            "-b",
+            "feature/wt",
+            worktree_path.to_str().unwrap(),
+        ],
+    )?;
+    std::env::set_current_dir(&worktree_path)?;
+    let worktree_manager = GitBranchDescManager::new(".")?;
+    assert_eq!(worktree_manager.get_current_branch()?, "feature/wt");
+    assert_eq!(
+        worktree_manager.read_current_branch_description()?,
+        "From a subdirectory"
+    );
+    worktree_manager.write_current_branch_description("Worktree branch")?;
+    worktree_manager.commit_current_branch_changes("feature/wt", true, false)?;
+    assert_eq!(
+        fs::read_to_string(repo_path.join("BRANCHREADME.md"))?,
+        "From a subdirectory"
+    );
+    assert_eq!(
+        git_in(repo_path, &["show", "feature/wt:BRANCHREADME.md"])?,
+        "Worktree branch"
+    );
+
+    // Branches checked out in another worktree are not committed to behind its back
+    let err = worktree_manager
+        .commit_to_branch(&main_branch, "Behind its back", true, false)
+        .unwrap_err();
+    assert!(err.to_string().contains("checked out in the worktree"));
+    let err = test_repo
+        .manager
+        .commit_to_branch("feature/wt", "Behind its back", true, false)
+        .unwrap_err();
+    assert!(err.to_string().contains("checked out in the worktree"));
+
+    // Bare mirrors get tree-only commits on HEAD's branch
+    let bare_dir = TempDir::new()?;
+    let bare_path = bare_dir.path().join("mirror.git");
+    git_in(
+        repo_path,
+        &[
+            "clone",
+            "-q",
+            "--mirror",
+            &test_repo.repo_path,
+            bare_path.to_str().unwrap(),
+        ],
+    )?;
+    git_in(&bare_path, &["config", "user.name", "Test User"])?;
+    git_in(&bare_path, &["config", "user.email", "test@example.com"])?;
+    std::env::set_current_dir(&bare_path)?;
+    let bare_manager = GitBranchDescManager::new(".")?;
+    assert_eq!(
+        bare_manager.read_current_branch_description()?,
+        "From a subdirectory"
+    );
+    bare_manager.edit_description_v2(
+        None,
+        InputSource::CommandLine(Some("Edited in the mirror".to_string())),
+        false,
+        120,
+        false,
+        false,
+        false,
+    )?;
+    assert_eq!(
+        git_in(&bare_path, &["show", "HEAD:BRANCHREADME.md"])?,
+        "Edited in the mirror"
+    );
+    assert_eq!(
+        git_in(&bare_path, &["log", "-1", "--format=%s", &main_branch])?,
+        "Update branch description [skip ci]"
+    );
+    assert!(
+        bare_manager
+            .commit_current_branch_changes(&main_branch, true, false)
+            .is_err()
+    );
+
+    std::env::set_current_dir(original_dir)?;
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_description_commit_leaves_other_staged_changes() -> Result<()> {
+    let test_repo = TestRepo::new()?;
+    let original_dir = std::env::current_dir()?;
+    test_repo.set_working_directory();
+    let main_branch = test_repo.manager.get_current_branch()?;
+    let git = |args: &[&str]| -> Result<String> {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(&test_repo.repo_path)
+            .output()?;
+        Ok(String::from_utf8(output.stdout)?.trim().to_string())
+    };
+
+    fs::write(
+        format!("{}/feature.rs", test_repo.repo_path),
+        "fn main() {}",
+    )?;
+    fs::write(
+        format!("{}/README.md", test_repo.repo_path),
+        "# Test Repository\n\nMore",
+    )?;
+    git(&["add", "feature.rs", "README.md"])?;
+
+    test_repo
+        .manager
+        .write_current_branch_description("Only this file")?;
+    test_repo
+        .manager
+        .commit_current_branch_changes(&main_branch, false, false)?;
+
+    assert_eq!(
+        git(&["show", "--name-only", "--format=", "HEAD"])?,
+        "BRANCHREADME.md"
+    );
+    assert_eq!(
+        git(&["status", "--porcelain"])?,
+        "M  README.md\nA  feature.rs"
+    );
+
+    std::env::set_current_dir(original_dir)?;
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_watch_description_file() -> Result<()> {
+    let test_repo = TestRepo::new()?;
+    let original_dir = std::env::current_dir()?;
+    test_repo.set_working_directory();
+    let main_branch = test_repo.manager.get_current_branch()?;
+    let input_dir = TempDir::new()?;
+    let input = input_dir.path().join("description.md");
+    fs::write(&input, "First")?;
+
+    // Each poll simulates the next save of the editor
+    let mut saves = vec![
+        Box::new(|path: &std::path::Path| fs::write(path, "First")) as Box<dyn Fn(&_) -> _>,
+        // A save that cannot be read must not end the watch
+        Box::new(|path: &std::path::Path| {
+            fs::remove_file(path)?;
+            fs::create_dir(path)
+        }),
+        Box::new(|path: &std::path::Path| {
+            fs::remove_dir(path)?;
+            fs::write(path, "Second")
+        }),
+    ]
+    .into_iter();
+    test_repo.manager.watch_description_file_until(
+        Some(main_branch.clone()),
+        &input,
+        false,
+        true,
+        std::time::Duration::from_millis(20),
+        &mut "".as_bytes(),
+        || match saves.next() {
+            Some(save) => {
+                save(&input).unwrap();
+                true
+            }
+            None => false,
+        },
+    )?;
+
+    let log = Command::new("git")
+        .args(["log", "--format=%s", &main_branch])
+        .current_dir(&test_repo.repo_path)
+        .output()?;
+    assert_eq!(
+        String::from_utf8(log.stdout)?,
+        "Update branch description [skip ci]\nAdd branch description [skip ci]\nInitial commit\n"
+    );
+    assert_eq!(
+        test_repo
+            .manager
+            .read_branch_description_from_git(&main_branch)?
+            .as_deref(),
+        Some("Second")
+    );
+
+    std::env::set_current_dir(original_dir)?;
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_watch_other_branch_confirmation() -> Result<()> {
+    let test_repo = TestRepo::new()?;
+    let original_dir = std::env::current_dir()?;
+    test_repo.set_working_directory();
+    let main_branch = test_repo.manager.get_current_branch()?;
+    test_repo.create_branch("feature/watched")?;
+    test_repo.checkout_branch(&main_branch)?;
+    let input_dir = TempDir::new()?;
+    let input = input_dir.path().join("description.md");
+    fs::write(&input, "First")?;
+
+    // Declining stops before watching, so no save is ever committed
+    let mut polls = 0;
+    test_repo.manager.watch_description_file_until(
+        Some("feature/watched".to_string()),
+        &input,
+        false,
+        false,
+        std::time::Duration::from_millis(20),
+        &mut "n\n".as_bytes(),
+        || {
+            polls += 1;
+            false
+        },
+    )?;
+    assert_eq!(polls, 0);
+    assert_eq!(
+        test_repo
+            .manager
+            .read_branch_description_from_git("feature/watched")?,
+        None
+    );
+
+    // Confirming once covers every later save
+    let mut saves = ["Second", "Third"].into_iter();
+    test_repo.manager.watch_description_file_until(
+        Some("feature/watched".to_string()),
+        &input,
+        false,
+        false,
+        std::time::Duration::from_millis(20),
+        &mut "y\n".as_bytes(),
+        || match saves.next() {
+            Some(content) => {
+                fs::write(&input, content).unwrap();
+                true
+            }
+            None => false,
+        },
+    )?;
+    assert_eq!(
+        test_repo
+            .manager
+            .read_branch_description_from_git("feature/watched")?
+            .as_deref(),
+        Some("Third")
+    );
+
+    std::env::set_current_dir(original_dir)?;
+    Ok(())
+}
+
 #[test]
 fn test_utility_functions() {
     // Test terminal width function
@@ -340,6 +340,83 @@ fn test_terminal_width_boundary_conditions() {
     }
 }
 
+#[test]
+#[serial]
+fn test_github_issue_with_mock_api() -> Result<()> {
+    let mock_repo = MockTestRepo::new()?;
+    let mut server = mockito::Server::new();
+
+    let mock = server
+        .mock("GET", "/repos/octo/widgets/issues/7")
+        .match_header("user-agent", "git-branch-desc")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"number":7,"title":"Add widget export","body":"Export widgets as CSV"}"#)
+        .expect_at_least(1)
+        .create();
+
+    let content = git_branch_desc::fetch_github_issue(&server.url(), "octo/widgets", "7", None)?;
+    assert_eq!(content, "Add widget export\n\nExport widgets as CSV");
+
+    // Resolve owner/repo from the origin remote and the API URL from git config
+    // (skipped when the environment already points at a real GitHub API)
+    if std::env::var("GITHUB_API_URL").is_err() && std::env::var("GITHUB_REPOSITORY").is_err() {
+        Command::new("git")
+            .args(["remote", "add", "origin", "git@github.com:octo/widgets.git"])
+            .current_dir(&mock_repo.repo_path)
+            .output()?;
+        Command::new("git")
+            .args(["config", "branchdesc.githubApiUrl", &server.url()])
+            .current_dir(&mock_repo.repo_path)
+            .output()?;
+
+        let content = mock_repo.manager.get_github_issue_content("#7", false, 0)?;
+        assert_eq!(content, "Add widget export\n\nExport widgets as CSV");
+    }
+
+    mock.assert();
+    Ok(())
+}
+
+#[test]
+fn test_github_issue_mock_api_errors() {
+    let mut server = mockito::Server::new();
+    let _mock = server
+        .mock("GET", "/repos/octo/widgets/issues/404")
+        .with_status(404)
+        .create();
+
+    let result = git_branch_desc::fetch_github_issue(&server.url(), "octo/widgets", "404", None);
+    assert!(result.is_err());
+    let error_msg = format!("{}", result.unwrap_err());
+    assert!(error_msg.contains("404"));
+}
+
+#[test]
+fn test_jira_issue_with_mock_api() -> Result<()> {
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("GET", "/rest/api/2/issue/PROJ-42")
+        .match_query(mockito::Matcher::UrlEncoded(
+            "fields".into(),
+            "summary,description".into(),
+        ))
+        .match_header("authorization", "Basic dXNlcjp0b2tlbg==")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"key":"PROJ-42","fields":{"summary":"Export report","description":"h2. Scope\n* *CSV* export"}}"#,
+        )
+        .create();
+
+    let content =
+        git_branch_desc::fetch_jira_issue(&server.url(), "PROJ-42", Some("user"), Some("token"))?;
+    assert_eq!(content, "Export report\n\n## Scope\n- **CSV** export");
+
+    mock.assert();
+    Ok(())
+}
+
 // Mock AI server tests - Temporarily disabled due to mockito compatibility issues
 // These would test AI integration with mocked HTTP responses
 #[test]
@@ -357,6 +434,114 @@ fn test_ai_functionality_placeholder() {
     // This test ensures the AI-related code compiles correctly
 }
 
+#[test]
+fn test_ollama_provider_with_mock_api() -> Result<()> {
+    let mock_repo = MockTestRepo::new()?;
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("POST", "/api/chat")
+        .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+            "model": "qwen2.5:14b",
+            "stream": true,
+            "options": {"temperature": 0.2}
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/x-ndjson")
+        .with_body(concat!(
+            r#"{"message":{"role":"assistant","content":"Adds "},"done":false}"#,
+            "\n",
+            r#"{"message":{"role":"assistant","content":"CSV export."},"done":false}"#,
+            "\n",
+            r#"{"message":{"role":"assistant","content":""},"done":true}"#,
+            "\n",
+        ))
+        .create();
+
+    for (key, value) in [
+        ("branchdesc.ai.endpoint", server.url()),
+        ("branchdesc.ai.model", "qwen2.5:14b".to_string()),
+        ("branchdesc.ai.temperature", "0.2".to_string()),
+    ] {
+        Command::new("git")
+            .args(["config", key, &value])
+            .current_dir(&mock_repo.repo_path)
+            .output()?;
+    }
+
+    // Environment variables take precedence over git config
+    if std::env::var_os("BRANCHDESC_AI_PROVIDER").is_some()
+        || std::env::var_os("BRANCHDESC_AI_ENDPOINT").is_some()
+    {
+        return Ok(());
+    }
+
+    let summary = mock_repo
+        .manager
+        .ai_summarize_content("Long issue text about exports", 10)?;
+    assert_eq!(summary, "Adds CSV export.");
+
+    mock.assert();
+    Ok(())
+}
+
+#[test]
+fn test_openai_provider_with_mock_api() -> Result<()> {
+    let mock_repo = MockTestRepo::new()?;
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("POST", "/v1/chat/completions")
+        .match_header("authorization", "Bearer secret")
+        .match_body(mockito::Matcher::PartialJson(
+            serde_json::json!({"model": "local-model"}),
+        ))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"choices":[{"message":{"role":"assistant","content":"Summary: Fixes login."}}]}"#,
+        )
+        .create();
+
+    // Provider, endpoint and key from git config, the model from the repository config file
+    fs::write(
+        std::path::Path::new(&mock_repo.repo_path).join(".branchdesc.toml"),
+        "[ai]\nmodel = \"local-model\"\n",
+    )?;
+    for (key, value) in [
+        ("branchdesc.ai.provider", "openai".to_string()),
+        ("branchdesc.ai.endpoint", format!("{}/v1", server.url())),
+        ("branchdesc.ai.apiKey", "secret".to_string()),
+    ] {
+        Command::new("git")
+            .args(["config", key, &value])
+            .current_dir(&mock_repo.repo_path)
+            .output()?;
+    }
+
+    if std::env::vars().any(|(key, _)| key.starts_with("BRANCHDESC_AI_")) {
+        return Ok(());
+    }
+
+    let settings = mock_repo.manager.ai_settings()?;
+    assert_eq!(settings.provider, git_branch_desc::ai::ProviderKind::OpenAi);
+
+    let summary = mock_repo
+        .manager
+        .ai_summarize_content("Long issue text about login", 10)?;
+    assert_eq!(summary, "Fixes login.");
+
+    mock.assert();
+
+    // Unknown providers are rejected with a helpful message
+    Command::new("git")
+        .args(["config", "branchdesc.ai.provider", "gpt"])
+        .current_dir(&mock_repo.repo_path)
+        .output()?;
+    let error = mock_repo.manager.ai_settings().unwrap_err();
+    assert!(error.to_string().contains("Unknown AI provider"));
+
+    Ok(())
+}
+
 #[test]
 #[serial]
 fn test_interactive_input_simulation() -> Result<()> {